@@ -0,0 +1,279 @@
+// a small HTTP API for driving a game from scripts and dashboards, localhost only.
+//
+//   POST /board          {"difficulty": "easy"} or {"width": 9, "height": 9, "mines": 10},
+//                        optionally with "seed" and "pure_random"
+//   GET  /board          the visible state
+//   POST /board/reveal   {"x": 3, "y": 4}
+//   POST /board/flag     {"x": 3, "y": 4}
+//   POST /board/chord    {"x": 3, "y": 4}
+//   GET  /stats          win rates and times for every mode, from the statistics file
+//   GET  /schema         JSON schema for all of the above, also in api-schema.json
+//
+// successful calls answer 200 with the same state as protocol.rs, anything else answers
+// with an error status and {"error": "..."}. Only one board is played at a time and every
+// client shares it, so the GUI can show what the API is doing.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::objs::Board;
+use crate::protocol::{Command, Response, Session};
+use crate::stats::{self, GameMode};
+
+pub const DEFAULT_API_PORT: u16 = 7880;
+
+pub const SCHEMA: &str = include_str!("../api-schema.json");
+
+// bodies bigger than this are not a board request
+const MAX_BODY: usize = 64 * 1024;
+
+#[derive(Deserialize)]
+struct NewBoard {
+    difficulty: Option<String>,
+    width: Option<usize>,
+    height: Option<usize>,
+    mines: Option<usize>,
+    seed: Option<u64>,
+    #[serde(default)]
+    pure_random: bool,
+}
+
+#[derive(Deserialize)]
+struct Position {
+    x: usize,
+    y: usize,
+}
+
+#[derive(Serialize)]
+struct ModeStats {
+    mode: GameMode,
+    games: u32,
+    wins: u32,
+    win_rate: f64,
+    current_streak: u32,
+    best_streak: u32,
+    best_time_ms: Option<u64>,
+    average_time_ms: Option<u64>,
+}
+
+#[derive(Default)]
+struct Shared {
+    session: Session,
+    // goes up with every change to the board, so a frontend knows when to redraw
+    version: u64,
+}
+
+pub struct ApiServer {
+    address: SocketAddr,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl ApiServer {
+    // starts serving in the background, refuses anything but a loopback address
+    pub fn start(address: &str) -> io::Result<ApiServer> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        if !address.ip().is_loopback() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "the API only listens on localhost"));
+        }
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let serving = Arc::clone(&shared);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = Arc::clone(&serving);
+                thread::spawn(move || {
+                    // the client hung up, nothing to answer
+                    let _ = handle_connection(stream, &shared);
+                });
+            }
+        });
+
+        Ok(ApiServer { address, shared })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn version(&self) -> u64 {
+        self.shared.lock().unwrap().version
+    }
+
+    // a copy of the board the API is playing, with the version it belongs to
+    pub fn board(&self) -> Option<(Board, u64)> {
+        let shared = self.shared.lock().unwrap();
+        shared.session.board().map(|board| (board.clone(), shared.version))
+    }
+
+    // hands a board played elsewhere to the API, returns the new version
+    pub fn set_board(&self, board: Board) -> u64 {
+        let mut shared = self.shared.lock().unwrap();
+        shared.session.replace_board(board);
+        shared.version += 1;
+        shared.version
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "request body too large"));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(Request {
+        method,
+        path,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn write_response(mut stream: TcpStream, status: u16, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn error(status: u16, message: impl Into<String>) -> (u16, String) {
+    let body = serde_json::to_string(&Response::Error { error: message.into() }).unwrap();
+    (status, body)
+}
+
+fn parse<'a, T: Deserialize<'a>>(body: &'a str) -> Result<T, (u16, String)> {
+    serde_json::from_str(body).map_err(|err| error(400, format!("bad request body: {}", err)))
+}
+
+fn handle_connection(stream: TcpStream, shared: &Mutex<Shared>) -> io::Result<()> {
+    let (status, body) = match read_request(&stream) {
+        Ok(request) => route(&request, shared).unwrap_or_else(|err| err),
+        Err(err) => error(400, err.to_string()),
+    };
+    write_response(stream, status, &body)
+}
+
+fn route(request: &Request, shared: &Mutex<Shared>) -> Result<(u16, String), (u16, String)> {
+    let command = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/schema") => return Ok((200, SCHEMA.to_string())),
+        ("GET", "/stats") => return stats_response(),
+        ("GET", "/board") => Command::State,
+        ("POST", "/board") => {
+            let new: NewBoard = parse(&request.body)?;
+            Command::New {
+                difficulty: new.difficulty,
+                width: new.width,
+                height: new.height,
+                mines: new.mines,
+                seed: new.seed,
+                pure_random: new.pure_random,
+            }
+        }
+        ("POST", "/board/reveal") | ("POST", "/board/flag") | ("POST", "/board/chord") => {
+            let Position { x, y } = parse(&request.body)?;
+            match request.path.as_str() {
+                "/board/reveal" => Command::Reveal { x, y },
+                "/board/flag" => Command::Flag { x, y },
+                _ => Command::Chord { x, y },
+            }
+        }
+        (_, "/schema") | (_, "/stats") | (_, "/board") | (_, "/board/reveal") | (_, "/board/flag")
+        | (_, "/board/chord") => return Err(error(405, format!("{} isn't allowed on {}", request.method, request.path))),
+        _ => return Err(error(404, format!("no endpoint at {}", request.path))),
+    };
+
+    let changes_board = !matches!(command, Command::State);
+    let mut shared = shared.lock().unwrap();
+    let response = shared.session.handle(command);
+    if let Response::Error { .. } = response {
+        return Err((400, serde_json::to_string(&response).unwrap()));
+    }
+    if changes_board {
+        shared.version += 1;
+    }
+    Ok((200, serde_json::to_string(&response).unwrap()))
+}
+
+fn stats_response() -> Result<(u16, String), (u16, String)> {
+    let records = stats::load_records(&stats::stats_path()).map_err(|err| error(500, err.to_string()))?;
+    let modes = [GameMode::Easy, GameMode::Medium, GameMode::Hard, GameMode::Custom, GameMode::Daily, GameMode::TimeAttack, GameMode::Lives];
+    let summaries: Vec<ModeStats> = modes
+        .iter()
+        .map(|&mode| {
+            let summary = stats::summarize(&records, mode);
+            ModeStats {
+                mode,
+                games: summary.games,
+                wins: summary.wins,
+                win_rate: summary.win_rate(),
+                current_streak: summary.current_streak,
+                best_streak: summary.best_streak,
+                best_time_ms: summary.best_time.map(|time| time.as_millis() as u64),
+                average_time_ms: summary.average_time.map(|time| time.as_millis() as u64),
+            }
+        })
+        .collect();
+    Ok((200, serde_json::to_string(&summaries).unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objs::MAX_SIZE;
+
+    #[test]
+    fn the_schema_allows_the_sizes_the_server_does() {
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+        let properties = &schema["$defs"]["NewBoard"]["properties"];
+        for side in ["width", "height"] {
+            assert_eq!(properties[side]["minimum"], 3);
+            assert_eq!(properties[side]["maximum"], MAX_SIZE);
+        }
+        assert!(Board::check_size(3, MAX_SIZE, 1).is_ok());
+        assert!(Board::check_size(2, MAX_SIZE, 1).is_err());
+        assert_eq!(properties["mines"]["maximum"], u8::MAX);
+        assert!(Board::check_size(MAX_SIZE, MAX_SIZE, u8::MAX as usize).is_ok());
+        assert!(Board::check_size(MAX_SIZE, MAX_SIZE, u8::MAX as usize + 1).is_err());
+    }
+}
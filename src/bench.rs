@@ -0,0 +1,262 @@
+// plays lots of seeded games with a bot strategy to see how hard a kind of board really is.
+// Every game is played through the player view, like any other frontend would.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::gym::Action;
+use crate::objs::{Board, GameResult};
+use crate::solver;
+use crate::view::{Cell, PlayerView};
+
+// keeps a strategy's own dice apart from the layout dealt with the same seed
+const STRATEGY_SALT: u64 = 0x7374_7261_7465_6779;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    // reveals hidden tiles at random
+    Random,
+    // flags and clears around single numbers, guesses at random when that runs out
+    Simple,
+    // the full solver from solver.rs, guesses the safest tile when it has to
+    Solver,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Random, Strategy::Simple, Strategy::Solver];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Random => "random",
+            Strategy::Simple => "simple",
+            Strategy::Solver => "solver",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        Strategy::ALL.into_iter().find(|strategy| strategy.name().eq_ignore_ascii_case(name))
+    }
+
+    // the next move and whether it is a guess, None when there is nothing left to do
+    fn choose(&self, view: PlayerView, rng: &mut StdRng) -> Option<(Action, bool)> {
+        match self {
+            Strategy::Random => random_reveal(view, rng),
+            Strategy::Simple => simple_rules(view).map(|action| (action, false)).or_else(|| random_reveal(view, rng)),
+            Strategy::Solver => {
+                let analysis = solver::analyze(view);
+                if let Some(&(x, y)) = analysis.safe_tiles().first() {
+                    return Some((Action::Reveal { x, y }, false));
+                }
+                analysis.best_guess().map(|(x, y)| (Action::Reveal { x, y }, true))
+            }
+        }
+    }
+}
+
+fn random_reveal(view: PlayerView, rng: &mut StdRng) -> Option<(Action, bool)> {
+    let hidden: Vec<(usize, usize)> = (0..view.height())
+        .flat_map(|y| (0..view.width()).map(move |x| (x, y)))
+        .filter(|&(x, y)| view.cell(x, y) == Cell::Hidden)
+        .collect();
+    hidden.choose(rng).map(|&(x, y)| (Action::Reveal { x, y }, true))
+}
+
+// the two rules everyone learns first, looking at one number at a time
+fn simple_rules(view: PlayerView) -> Option<Action> {
+    for y in 0..view.height() {
+        for x in 0..view.width() {
+            let adjacent_mines = match view.cell(x, y) {
+                Cell::Number(adjacent_mines) => adjacent_mines as usize,
+                _ => continue,
+            };
+            let neighbours = view.neighbours(x, y);
+            let flagged = neighbours.iter().filter(|&&(nx, ny)| matches!(view.cell(nx, ny), Cell::Flagged | Cell::Mine)).count();
+            let hidden: Vec<&(usize, usize)> =
+                neighbours.iter().filter(|&&(nx, ny)| view.cell(nx, ny) == Cell::Hidden).collect();
+
+            if let Some(&&(nx, ny)) = hidden.first() {
+                // every mine is flagged, the rest is safe
+                if flagged == adjacent_mines {
+                    return Some(Action::Reveal { x: nx, y: ny });
+                }
+                // every hidden tile has to be a mine
+                if flagged + hidden.len() == adjacent_mines {
+                    return Some(Action::Flag { x: nx, y: ny });
+                }
+            }
+        }
+    }
+    None
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LossReason {
+    // the very first reveal hit a mine
+    FirstClick,
+    // a guess next to revealed numbers
+    FrontierGuess,
+    // a guess with no revealed number around it
+    BlindGuess,
+    // a move the strategy thought was safe, e.g. after a wrong flag
+    Deduction,
+}
+
+impl LossReason {
+    pub const ALL: [LossReason; 4] = [
+        LossReason::FirstClick,
+        LossReason::FrontierGuess,
+        LossReason::BlindGuess,
+        LossReason::Deduction,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LossReason::FirstClick => "first click",
+            LossReason::FrontierGuess => "frontier guess",
+            LossReason::BlindGuess => "blind guess",
+            LossReason::Deduction => "deduction",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BenchConfig {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub games: usize,
+    pub seed: u64,
+    pub pure_random: bool,
+    pub strategy: Strategy,
+    pub threads: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BenchReport {
+    pub games: usize,
+    pub wins: usize,
+    // summed over won games, see bv3_per_move
+    pub bv3: u64,
+    pub moves: u64,
+    pub losses: [usize; 4],
+    pub elapsed: Duration,
+}
+
+impl BenchReport {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+
+    // bots don't take time to think, so a move stands in for a second of 3BV/s
+    pub fn bv3_per_move(&self) -> f64 {
+        if self.moves == 0 {
+            return 0.0;
+        }
+        self.bv3 as f64 / self.moves as f64
+    }
+
+    pub fn losses(&self, reason: LossReason) -> usize {
+        self.losses[reason as usize]
+    }
+
+    fn merge(&mut self, other: &BenchReport) {
+        self.games += other.games;
+        self.wins += other.wins;
+        self.bv3 += other.bv3;
+        self.moves += other.moves;
+        for (losses, other) in self.losses.iter_mut().zip(other.losses) {
+            *losses += other;
+        }
+    }
+}
+
+// plays one game to the end and adds it to the report
+fn play(config: &BenchConfig, seed: u64, report: &mut BenchReport) {
+    let mut board = Board::with_seed(config.width, config.height, config.mines, config.pure_random, seed);
+    let mut rng = StdRng::seed_from_u64(seed ^ STRATEGY_SALT);
+    let mut moves = 0;
+    let mut loss = None;
+
+    // a strategy that keeps going back and forth gives up eventually
+    for _ in 0..config.width * config.height * 3 {
+        let view = board.view();
+        let (action, guess) = match config.strategy.choose(view, &mut rng) {
+            Some(choice) => choice,
+            None => break,
+        };
+
+        let reason = match action {
+            Action::Reveal { .. } if view.score() == 0 => LossReason::FirstClick,
+            Action::Reveal { x, y } if guess => {
+                if view.neighbours(x, y).iter().any(|&(nx, ny)| view.cell(nx, ny).is_revealed()) {
+                    LossReason::FrontierGuess
+                } else {
+                    LossReason::BlindGuess
+                }
+            }
+            _ => LossReason::Deduction,
+        };
+
+        moves += 1;
+        let result = match action {
+            Action::Reveal { x, y } => board.reveal(x, y),
+            Action::Flag { x, y } => board.flag(x, y),
+            Action::Chord { x, y } => board.chord(x, y),
+        };
+        match result {
+            GameResult::Continue => {}
+            GameResult::Won => break,
+            GameResult::Lost => {
+                loss = Some(reason);
+                break;
+            }
+        }
+    }
+
+    report.games += 1;
+    match (board.get_result(), loss) {
+        (GameResult::Won, _) => {
+            report.wins += 1;
+            report.bv3 += board.get_3bv() as u64;
+            report.moves += moves;
+        }
+        (_, Some(reason)) => report.losses[reason as usize] += 1,
+        _ => {}
+    }
+}
+
+// plays every game, spread over the threads, game i always gets seed + i
+pub fn run(config: &BenchConfig) -> BenchReport {
+    let started = Instant::now();
+    let next = AtomicUsize::new(0);
+    let total = Mutex::new(BenchReport::default());
+
+    thread::scope(|scope| {
+        for _ in 0..config.threads.max(1) {
+            scope.spawn(|| {
+                let mut report = BenchReport::default();
+                loop {
+                    let game = next.fetch_add(1, Ordering::Relaxed);
+                    if game >= config.games {
+                        break;
+                    }
+                    play(config, config.seed.wrapping_add(game as u64), &mut report);
+                }
+                total.lock().unwrap().merge(&report);
+            });
+        }
+    });
+
+    let mut report = total.into_inner().unwrap();
+    report.elapsed = started.elapsed();
+    report
+}
@@ -0,0 +1,26 @@
+// headless HTTP API, see api.rs for the endpoints
+//
+// usage: minesweeper-api [address]
+//
+// listens on 127.0.0.1:7880 unless another localhost address is given
+
+use minesweeper::api::{ApiServer, DEFAULT_API_PORT};
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("127.0.0.1:{}", DEFAULT_API_PORT));
+
+    match ApiServer::start(&address) {
+        Ok(server) => println!("listening on http://{}", server.address()),
+        Err(err) => {
+            eprintln!("could not listen on {}: {}", address, err);
+            std::process::exit(1);
+        }
+    }
+
+    // the server runs on its own threads
+    loop {
+        std::thread::park();
+    }
+}
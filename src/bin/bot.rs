@@ -0,0 +1,26 @@
+// headless mode for bots, speaks the JSON lines protocol in protocol.rs over stdin/stdout
+//
+// usage: minesweeper-bot
+
+use std::io::{self, BufRead, Write};
+
+use minesweeper::protocol::Session;
+
+fn main() -> io::Result<()> {
+    let mut session = Session::new();
+    let mut out = io::stdout().lock();
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        writeln!(out, "{}", session.handle_line(&line))?;
+        out.flush()?;
+        if session.is_finished() {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+// line based client for the multiplayer server, see net.rs for the protocol
+//
+// usage: minesweeper-client <name> <room> [versus|coop] [easy|medium|hard] [address]
+//
+// then type `r x y` to reveal, `f x y` to flag, `c x y` to chord, `s` for the state,
+// `restart` for a new layout once the round is over and `q` to quit
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::thread;
+
+use minesweeper::net::{ClientMessage, RoomMode, ServerMessage, DEFAULT_PORT, PROTOCOL_VERSION};
+
+const USAGE: &str = "usage: minesweeper-client <name> <room> [versus|coop] [easy|medium|hard] [address]";
+
+fn send(stream: &mut TcpStream, message: &ClientMessage) -> io::Result<()> {
+    writeln!(stream, "{}", serde_json::to_string(message).unwrap())?;
+    stream.flush()
+}
+
+fn print_message(message: ServerMessage) {
+    match message {
+        ServerMessage::Welcome { version, .. } => println!("connected, protocol version {}", version),
+        ServerMessage::Joined { room, mode, players } => {
+            println!("room {} ({:?}): {}", room, mode, players.join(", "))
+        }
+        ServerMessage::State { by, state } => {
+            if !by.is_empty() {
+                println!("{} moved", by);
+            }
+            println!("   {}", (0..state.width).map(|x| (x % 10).to_string()).collect::<String>());
+            for (y, row) in state.board.iter().enumerate() {
+                println!("{:>2} {}", y, row);
+            }
+            println!("{:?}, {} flags of {} mines", state.status, state.flags, state.mines);
+        }
+        ServerMessage::Progress { players, winner } => {
+            for player in players {
+                println!("  {}: {:?}, {} cleared", player.name, player.status, player.score);
+            }
+            if let Some(winner) = winner {
+                println!("{} won the race", winner);
+            }
+        }
+        ServerMessage::Error { error } => println!("error: {}", error),
+    }
+}
+
+fn parse_move(line: &str) -> Option<ClientMessage> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["s"] | ["state"] => Some(ClientMessage::State),
+        ["restart"] => Some(ClientMessage::Restart),
+        ["q"] | ["quit"] => Some(ClientMessage::Quit),
+        [action, x, y] => {
+            let (x, y) = (x.parse().ok()?, y.parse().ok()?);
+            match *action {
+                "r" | "reveal" => Some(ClientMessage::Reveal { x, y }),
+                "f" | "flag" => Some(ClientMessage::Flag { x, y }),
+                "c" | "chord" => Some(ClientMessage::Chord { x, y }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 2 {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    }
+
+    let mut mode = RoomMode::Versus;
+    let mut difficulty = "easy".to_string();
+    let mut address = format!("127.0.0.1:{}", DEFAULT_PORT);
+    for arg in &args[2..] {
+        match arg.as_str() {
+            "versus" => mode = RoomMode::Versus,
+            "coop" => mode = RoomMode::Coop,
+            "easy" | "medium" | "hard" => difficulty = arg.clone(),
+            _ => address = arg.clone(),
+        }
+    }
+
+    let mut stream = TcpStream::connect(&address)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    thread::spawn(move || {
+        for line in reader.lines() {
+            match line.map(|line| serde_json::from_str::<ServerMessage>(&line)) {
+                Ok(Ok(message)) => print_message(message),
+                Ok(Err(err)) => println!("unreadable message from the server: {}", err),
+                Err(_) => break,
+            }
+        }
+        println!("disconnected");
+        std::process::exit(0);
+    });
+
+    send(&mut stream, &ClientMessage::Hello {
+        version: PROTOCOL_VERSION,
+        name: args[0].clone(),
+    })?;
+    send(&mut stream, &ClientMessage::Join {
+        room: args[1].clone(),
+        mode,
+        difficulty: Some(difficulty),
+        width: None,
+        height: None,
+        mines: None,
+        pure_random: false,
+    })?;
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_move(&line) {
+            Some(ClientMessage::Quit) => break,
+            Some(message) => send(&mut stream, &message)?,
+            None => println!("moves are `r x y`, `f x y`, `c x y`, `s`, `restart` or `q`"),
+        }
+    }
+    send(&mut stream, &ClientMessage::Quit)
+}
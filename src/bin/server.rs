@@ -0,0 +1,30 @@
+// multiplayer server, see net.rs for the protocol
+//
+// usage: minesweeper-server [address]
+//
+// listens on 127.0.0.1:7878 unless another address is given
+
+use std::net::TcpListener;
+use std::sync::Arc;
+
+use minesweeper::net::{Server, DEFAULT_PORT};
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| format!("127.0.0.1:{}", DEFAULT_PORT));
+
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("could not listen on {}: {}", address, err);
+            std::process::exit(1);
+        }
+    };
+    println!("listening on {}", address);
+
+    if let Err(err) = Arc::new(Server::new()).serve(listener) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
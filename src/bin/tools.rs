@@ -0,0 +1,166 @@
+// headless tools that play lots of games without a window
+//
+// usage: minesweeper-tools <command> [options], see USAGE
+
+use minesweeper::bench::{self, BenchConfig, LossReason, Strategy};
+use minesweeper::dataset::{self, ExportConfig};
+use minesweeper::{Board, Difficulty};
+
+const USAGE: &str = "usage: minesweeper-tools <command> [options]
+
+commands:
+  export    play seeded games with the solver and write labelled positions as NPY files
+  bench     play seeded games with a strategy on every core and report how it went
+
+export options:
+  --games <n>         games to play (default 1000)
+  --width <n>         board width (default 16)
+  --height <n>        board height (default 16)
+  --density <d>       mines per tile, give it more than once to mix densities (default 0.15)
+  --seed <n>          seed of the first game, the others count up from it (default 0)
+  --clustered         place mines in clusters instead of uniformly at random
+  --out <prefix>      writes <prefix>-boards.npy and <prefix>-labels.npy (default dataset)
+
+bench options:
+  --games <n>         games to play for every placement (default 10000)
+  --difficulty <easy|medium|hard>
+                      board to play (default medium)
+  --width <n> --height <n> --mines <n>
+                      a custom board instead
+  --strategy <random|simple|solver>
+                      how the games are played (default solver)
+  --placement <both|pure-random|clustered>
+                      mine placements to compare (default both)
+  --seed <n>          seed of the first game, the others count up from it (default 0)
+  --threads <n>       threads to play on (default one per core)";
+
+fn value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value.parse().map_err(|_| format!("{} got `{}`, which isn't a number", flag, value))
+}
+
+fn export(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let mut config = ExportConfig {
+        width: 16,
+        height: 16,
+        densities: Vec::new(),
+        games: 1000,
+        seed: 0,
+        pure_random: true,
+    };
+    let mut out = "dataset".to_string();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => config.games = value(&arg, args.next())?,
+            "--width" => config.width = value(&arg, args.next())?,
+            "--height" => config.height = value(&arg, args.next())?,
+            "--density" => config.densities.push(value(&arg, args.next())?),
+            "--seed" => config.seed = value(&arg, args.next())?,
+            "--clustered" => config.pure_random = false,
+            "--out" => out = args.next().ok_or("--out needs a prefix")?,
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+    if config.densities.is_empty() {
+        config.densities.push(0.15);
+    }
+    if config.densities.iter().any(|density| !(0.0..1.0).contains(density)) {
+        return Err("densities go from 0 up to 1".to_string());
+    }
+    for &density in &config.densities {
+        Board::check_safe_start(config.width, config.height, config.mines_for(density))?;
+    }
+
+    let summary = dataset::export(&config, &out).map_err(|err| err.to_string())?;
+    println!(
+        "{} samples from {} games ({} won) written to {}-boards.npy and {}-labels.npy",
+        summary.samples, summary.games, summary.wins, out, out
+    );
+    Ok(())
+}
+
+fn bench(mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    let (mut width, mut height, mut mines) = Difficulty::Medium.dimensions();
+    let mut config = BenchConfig {
+        width,
+        height,
+        mines,
+        games: 10000,
+        seed: 0,
+        pure_random: true,
+        strategy: Strategy::Solver,
+        threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+    };
+    let mut placements = vec![true, false];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => config.games = value(&arg, args.next())?,
+            "--difficulty" => {
+                let name = args.next().ok_or("--difficulty needs a value")?;
+                let difficulty = Difficulty::from_name(&name).ok_or_else(|| format!("unknown difficulty `{}`", name))?;
+                (width, height, mines) = difficulty.dimensions();
+            }
+            "--width" => width = value(&arg, args.next())?,
+            "--height" => height = value(&arg, args.next())?,
+            "--mines" => mines = value(&arg, args.next())?,
+            "--strategy" => {
+                let name = args.next().ok_or("--strategy needs a value")?;
+                config.strategy = Strategy::from_name(&name).ok_or_else(|| format!("unknown strategy `{}`", name))?;
+            }
+            "--placement" => {
+                placements = match args.next().as_deref() {
+                    Some("both") => vec![true, false],
+                    Some("pure-random") => vec![true],
+                    Some("clustered") => vec![false],
+                    _ => return Err("--placement is one of both, pure-random or clustered".to_string()),
+                }
+            }
+            "--seed" => config.seed = value(&arg, args.next())?,
+            "--threads" => config.threads = value(&arg, args.next())?,
+            _ => return Err(format!("unknown option `{}`", arg)),
+        }
+    }
+    Board::check_size(width, height, mines)?;
+    (config.width, config.height, config.mines) = (width, height, mines);
+
+    println!(
+        "{} games per placement on {}x{} with {} mines, {} strategy, {} threads",
+        config.games, width, height, mines, config.strategy.name(), config.threads
+    );
+    for pure_random in placements {
+        let report = bench::run(&BenchConfig { pure_random, ..config });
+        let lost = report.games - report.wins;
+        println!();
+        println!("{}", if pure_random { "pure random" } else { "clustered" });
+        println!("  win rate      {:.1}% ({} of {})", report.win_rate() * 100.0, report.wins, report.games);
+        println!("  3BV per move  {:.3} (won games, a move counts as a second)", report.bv3_per_move());
+        println!("  speed         {:.0} games/s", report.games as f64 / report.elapsed.as_secs_f64().max(1e-9));
+        for reason in LossReason::ALL {
+            let losses = report.losses(reason);
+            let share = if lost == 0 { 0.0 } else { losses as f64 / lost as f64 * 100.0 };
+            println!("  {:<14}{:>5.1}% of losses ({})", reason.name(), share, losses);
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("export") => export(args),
+        Some("bench") => bench(args),
+        Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command `{}`", command)),
+        None => Err("no command given".to_string()),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}\n\n{}", err, USAGE);
+        std::process::exit(2);
+    }
+}
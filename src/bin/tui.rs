@@ -0,0 +1,154 @@
+// terminal frontend, plays the same Board as the window version
+//
+// usage: minesweeper-tui [easy|medium|hard]
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
+use minesweeper::{palette, stats, Board, Cell, Difficulty, GameResult};
+
+const HELP: &str = "arrows/hjkl: move  space: reveal  f: flag  c: chord  n: new game  q: quit";
+
+struct Game {
+    board: Board,
+    difficulty: Difficulty,
+    cursor: (usize, usize),
+    result: GameResult,
+}
+
+impl Game {
+    fn new(difficulty: Difficulty) -> Game {
+        let (width, height, mines) = difficulty.dimensions();
+        Game {
+            board: Board::new(width, height, mines, false),
+            difficulty,
+            cursor: (0, 0),
+            result: GameResult::Continue,
+        }
+    }
+
+    fn move_cursor(&mut self, x: i32, y: i32) {
+        let width = self.board.get_width() as i32;
+        let height = self.board.get_height() as i32;
+        self.cursor.0 = (self.cursor.0 as i32 + x).clamp(0, width - 1) as usize;
+        self.cursor.1 = (self.cursor.1 as i32 + y).clamp(0, height - 1) as usize;
+    }
+
+    fn play(&mut self, key: KeyCode) {
+        if self.result != GameResult::Continue {
+            return;
+        }
+
+        let (x, y) = self.cursor;
+        let result = match key {
+            KeyCode::Char(' ') | KeyCode::Enter => self.board.reveal(x, y),
+            KeyCode::Char('c') => self.board.chord(x, y),
+            KeyCode::Char('f') => self.board.flag(x, y),
+            _ => return,
+        };
+
+        if result != GameResult::Continue {
+            // the status line is redrawn right after, so there's no good place to show this
+            let _ = stats::record_game(&self.board);
+        }
+        self.result = result;
+    }
+}
+
+fn rgb((r, g, b): (u8, u8, u8)) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+fn draw(out: &mut impl Write, game: &Game) -> io::Result<()> {
+    queue!(out, cursor::MoveTo(0, 0), terminal::Clear(terminal::ClearType::All))?;
+
+    let view = game.board.view();
+    queue!(
+        out,
+        Print(format!(
+            "Minesweeper - {}  Mines: {}  Time: {:.1}s\r\n\r\n",
+            game.difficulty.name(),
+            view.remaining_mines(),
+            game.board.get_elapsed_time().as_secs_f64()
+        ))
+    )?;
+
+    for y in 0..view.height() {
+        for x in 0..view.width() {
+            let (symbol, color) = match view.cell(x, y) {
+                Cell::Mine => ('*', rgb(palette::MINE_RGB)),
+                Cell::Number(0) => ('.', rgb(palette::number_rgb(0))),
+                Cell::Number(adjacent_mines) => ((b'0' + adjacent_mines) as char, rgb(palette::number_rgb(adjacent_mines))),
+                Cell::Flagged => ('F', rgb(palette::FLAG_RGB)),
+                Cell::Hidden => ('#', Color::Grey),
+            };
+
+            if (x, y) == game.cursor {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(out, SetForegroundColor(color), Print(symbol), ResetColor, SetAttribute(Attribute::Reset), Print(' '))?;
+        }
+        queue!(out, Print("\r\n"))?;
+    }
+
+    let status = match game.result {
+        GameResult::Continue => String::new(),
+        GameResult::Won => format!("You won! Score: {}", game.board.get_score()),
+        GameResult::Lost => format!("Game Over! Score: {}", game.board.get_score()),
+    };
+    queue!(out, Print(format!("\r\n{}\r\n{}\r\n", status, HELP)))?;
+    out.flush()
+}
+
+fn run(out: &mut impl Write, difficulty: Difficulty) -> io::Result<()> {
+    let mut game = Game::new(difficulty);
+    loop {
+        draw(out, &game)?;
+
+        // wake up now and then so the clock keeps ticking
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key.code,
+            _ => continue,
+        };
+
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('n') => game = Game::new(difficulty),
+            KeyCode::Left | KeyCode::Char('h') => game.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => game.move_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => game.move_cursor(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => game.move_cursor(0, 1),
+            key => game.play(key),
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let difficulty = match std::env::args().nth(1) {
+        None => Difficulty::Easy,
+        Some(name) => match Difficulty::from_name(&name) {
+            Some(difficulty) => difficulty,
+            None => {
+                eprintln!("usage: minesweeper-tui [easy|medium|hard]");
+                std::process::exit(2);
+            }
+        },
+    };
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = run(&mut out, difficulty);
+
+    // always give the terminal back, even if drawing failed
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
@@ -0,0 +1,205 @@
+// the campaign, hand made levels played in order. Levels are read from campaign.json in the
+// assets directory, progress is kept next to the other data files.
+//
+// a level looks like this, everything after "mines" can be left out:
+//
+//   {"name": "Crossroads", "width": 12, "height": 12, "mines": 20,
+//    "seed": 7, "pure_random": true, "topology": "wrap", "safe_start": [6, 6], "lives": 2,
+//    "time_limit_secs": 120, "goals": {"time_secs": 60, "efficiency": 0.6}}
+//
+// a fixed seed always deals the same layout, a "wrap" topology joins opposite edges, safe_start
+// reveals an opening before the clock starts and time_limit_secs plays the level as a time attack. Clearing a level is worth a star,
+// beating the time goal and the efficiency goal (3BV per click) are worth one more each.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::objs::{Board, GameResult, Topology};
+use crate::storage;
+
+pub const CAMPAIGN_FILE: &str = "campaign.json";
+pub const PROGRESS_FILE: &str = "minesweeper-campaign.json";
+
+pub const MAX_STARS: u8 = 3;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Goals {
+    pub time_secs: Option<u64>,
+    // 3BV per click, 1.0 is a perfect game
+    pub efficiency: Option<f64>,
+}
+
+fn one_life() -> u8 {
+    1
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub pure_random: bool,
+    #[serde(default)]
+    pub topology: Topology,
+    #[serde(default)]
+    pub safe_start: Option<(usize, usize)>,
+    #[serde(default = "one_life")]
+    pub lives: u8,
+    #[serde(default)]
+    pub time_limit_secs: Option<u64>,
+    #[serde(default)]
+    pub goals: Goals,
+}
+
+impl Level {
+    fn check(&self) -> Result<(), String> {
+        Board::with_topology(self.width, self.height, self.mines, self.pure_random, 0, self.topology, self.safe_start).map(|_| ())
+    }
+
+    pub fn time_limit(&self) -> Option<Duration> {
+        self.time_limit_secs.map(Duration::from_secs)
+    }
+
+    // a fresh board for the level, with the safe start already revealed
+    pub fn board(&self) -> Board {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let mut board = Board::with_topology(self.width, self.height, self.mines, self.pure_random, seed, self.topology, self.safe_start)
+            .expect("levels are checked when the campaign is loaded");
+        // the lives have to be set before anything is revealed
+        board.set_lives(self.lives);
+        if let Some((x, y)) = self.safe_start {
+            board.open_start(x, y);
+        }
+        board
+    }
+
+    // 0 until the level is cleared, then one star plus one for every goal met
+    pub fn stars(&self, board: &Board) -> u8 {
+        if board.get_result() != GameResult::Won {
+            return 0;
+        }
+
+        let mut stars = 1;
+        if let Some(time_secs) = self.goals.time_secs {
+            if board.get_elapsed_time() <= Duration::from_secs(time_secs) {
+                stars += 1;
+            }
+        }
+        if let Some(efficiency) = self.goals.efficiency {
+            if board.get_3bv() as f64 / board.get_clicks().max(1) as f64 >= efficiency {
+                stars += 1;
+            }
+        }
+        stars.min(MAX_STARS)
+    }
+}
+
+pub struct Campaign {
+    pub levels: Vec<Level>,
+}
+
+impl Campaign {
+    pub fn load(path: &Path) -> io::Result<Campaign> {
+        let levels: Vec<Level> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        for level in &levels {
+            level
+                .check()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("level {}: {}", level.name, err)))?;
+        }
+        Ok(Campaign { levels })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelProgress {
+    pub stars: u8,
+    pub best_time_ms: u64,
+}
+
+// the best result on every level cleared so far, by level name
+#[derive(Default, Serialize, Deserialize)]
+pub struct CampaignProgress {
+    levels: BTreeMap<String, LevelProgress>,
+}
+
+impl CampaignProgress {
+    pub fn get(&self, level: &Level) -> Option<&LevelProgress> {
+        self.levels.get(&level.name)
+    }
+
+    pub fn stars(&self, level: &Level) -> u8 {
+        self.get(level).map_or(0, |progress| progress.stars)
+    }
+
+    // the first level is always open, every other one once the level before it is cleared
+    pub fn is_unlocked(&self, campaign: &Campaign, index: usize) -> bool {
+        index == 0 || campaign.levels.get(index - 1).is_some_and(|level| self.stars(level) > 0)
+    }
+
+    pub fn total_stars(&self) -> u32 {
+        self.levels.values().map(|progress| progress.stars as u32).sum()
+    }
+
+    // keeps the best stars and time of a cleared level, returns the stars of this run
+    pub fn record(&mut self, level: &Level, board: &Board) -> u8 {
+        let stars = level.stars(board);
+        if stars == 0 {
+            return 0;
+        }
+
+        let time_ms = board.get_elapsed_time().as_millis() as u64;
+        let progress = self.levels.entry(level.name.clone()).or_insert(LevelProgress { stars, best_time_ms: time_ms });
+        progress.stars = progress.stars.max(stars);
+        progress.best_time_ms = progress.best_time_ms.min(time_ms);
+        stars
+    }
+
+    pub fn load(path: &Path) -> io::Result<CampaignProgress> {
+        storage::load_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+}
+
+pub fn progress_path() -> PathBuf {
+    storage::data_path(PROGRESS_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_shipped_campaign_loads() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join(CAMPAIGN_FILE);
+        let campaign = Campaign::load(&path).unwrap();
+        assert!(campaign.levels.iter().any(|level| level.topology == Topology::Wrap));
+        for level in &campaign.levels {
+            let board = level.board();
+            assert_eq!(board.get_topology(), level.topology);
+            assert_eq!(board.get_result(), GameResult::Continue, "{}", level.name);
+        }
+    }
+
+    #[test]
+    fn level_boards_get_the_level_lives() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join(CAMPAIGN_FILE);
+        let campaign = Campaign::load(&path).unwrap();
+        assert!(campaign.levels.iter().any(|level| level.lives > 1 && level.safe_start.is_some()));
+        for level in &campaign.levels {
+            let board = level.board();
+            assert_eq!(board.get_lives(), level.lives.max(1), "{}", level.name);
+            assert_eq!(board.get_clicks(), 0, "{}", level.name);
+        }
+    }
+}
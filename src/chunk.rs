@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+// chunks are square blocks of tiles, CHUNK_SIZE tiles to a side
+pub const CHUNK_SIZE: i32 = 16;
+
+// which chunk a tile lives in
+pub fn chunk_of(x: i32, y: i32) -> (i32, i32) {
+    (x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE))
+}
+
+// index of a tile inside its chunk
+pub fn index_of(x: i32, y: i32) -> usize {
+    (y.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + x.rem_euclid(CHUNK_SIZE)) as usize
+}
+
+#[derive(Clone)]
+pub struct ChunkMap<T> {
+    chunks: HashMap<(i32, i32), Vec<T>>,
+}
+
+impl<T> ChunkMap<T> {
+    pub fn new() -> ChunkMap<T> {
+        ChunkMap {
+            chunks: HashMap::new(),
+        }
+    }
+
+    pub fn has_chunk(&self, chunk: (i32, i32)) -> bool {
+        self.chunks.contains_key(&chunk)
+    }
+
+    // tiles must be CHUNK_SIZE * CHUNK_SIZE long, in row order
+    pub fn insert_chunk(&mut self, chunk: (i32, i32), tiles: Vec<T>) {
+        assert_eq!(tiles.len(), (CHUNK_SIZE * CHUNK_SIZE) as usize);
+        self.chunks.insert(chunk, tiles);
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<&T> {
+        self.chunks
+            .get(&chunk_of(x, y))
+            .map(|chunk| &chunk[index_of(x, y)])
+    }
+
+    pub fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut T> {
+        self.chunks
+            .get_mut(&chunk_of(x, y))
+            .map(|chunk| &mut chunk[index_of(x, y)])
+    }
+
+    // every tile in every loaded chunk, in no particular order
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.chunks.values_mut().flat_map(|chunk| chunk.iter_mut())
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+impl<T: Default> ChunkMap<T> {
+    // chunks covering the rectangle (0, 0) to (width, height), filled with T::default()
+    pub fn with_size(width: i32, height: i32) -> ChunkMap<T> {
+        let mut map = ChunkMap::new();
+        for cy in 0..(height + CHUNK_SIZE - 1) / CHUNK_SIZE {
+            for cx in 0..(width + CHUNK_SIZE - 1) / CHUNK_SIZE {
+                let tiles = (0..CHUNK_SIZE * CHUNK_SIZE).map(|_| T::default()).collect();
+                map.insert_chunk((cx, cy), tiles);
+            }
+        }
+        map
+    }
+}
+
+impl<T> Default for ChunkMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
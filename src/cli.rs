@@ -0,0 +1,126 @@
+// command line options for starting straight into a game
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::difficulty::Difficulty;
+use crate::objs::Board;
+use crate::save::{self, SaveError};
+
+pub const USAGE: &str = "usage: minesweeper [options]
+
+  --difficulty <easy|medium|hard>   start a preset board
+  --width <n> --height <n> --mines <n>
+                                    start a custom board
+  --load <file>                     continue a saved board
+  --seed <n>                        seed for the mine layout
+  --pure-random                     place mines uniformly at random
+  --clustered                       place mines in clusters (default)
+  --assets <dir>                    directory with the tile images
+  --broadcast <address>             stream the game to spectators, e.g. 127.0.0.1:7879
+  --spectate <address>              watch a game streamed with --broadcast
+  --delay <seconds>                 how far behind a spectator stays (default 0)
+  --api <address>                   serve the HTTP API next to the window, e.g. 127.0.0.1:7880
+  --help                            show this message";
+
+pub enum BoardChoice {
+    Preset(Difficulty),
+    Custom { width: usize, height: usize, mines: usize },
+    Save(PathBuf),
+}
+
+#[derive(Default)]
+pub struct LaunchOptions {
+    pub board: Option<BoardChoice>,
+    pub seed: Option<u64>,
+    pub pure_random: bool,
+    pub assets: Option<PathBuf>,
+    pub broadcast: Option<String>,
+    pub spectate: Option<String>,
+    pub delay: Duration,
+    pub api: Option<String>,
+    pub help: bool,
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got `{}`", flag, value))
+}
+
+impl LaunchOptions {
+    // args without the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<LaunchOptions, String> {
+        let mut options = LaunchOptions::default();
+        let mut width = None;
+        let mut height = None;
+        let mut mines = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--difficulty" => {
+                    let name = args.next().ok_or("--difficulty needs a value")?;
+                    let difficulty = Difficulty::from_name(&name).ok_or_else(|| format!("unknown difficulty `{}`", name))?;
+                    options.board = Some(BoardChoice::Preset(difficulty));
+                }
+                "--width" => width = Some(number(&arg, args.next())?),
+                "--height" => height = Some(number(&arg, args.next())?),
+                "--mines" => mines = Some(number(&arg, args.next())?),
+                "--load" => {
+                    let path = args.next().ok_or("--load needs a file")?;
+                    options.board = Some(BoardChoice::Save(PathBuf::from(path)));
+                }
+                "--seed" => options.seed = Some(number(&arg, args.next())?),
+                "--pure-random" => options.pure_random = true,
+                "--clustered" => options.pure_random = false,
+                "--assets" => {
+                    let dir = args.next().ok_or("--assets needs a directory")?;
+                    options.assets = Some(PathBuf::from(dir));
+                }
+                "--broadcast" => options.broadcast = Some(args.next().ok_or("--broadcast needs an address")?),
+                "--spectate" => options.spectate = Some(args.next().ok_or("--spectate needs an address")?),
+                "--api" => options.api = Some(args.next().ok_or("--api needs an address")?),
+                "--delay" => options.delay = Duration::from_secs(number(&arg, args.next())?),
+                "--help" | "-h" => options.help = true,
+                _ => return Err(format!("unknown option `{}`", arg)),
+            }
+        }
+
+        match (width, height, mines) {
+            (None, None, None) => {}
+            (Some(width), Some(height), Some(mines)) => {
+                if options.board.is_some() {
+                    return Err("a custom board can't be combined with --difficulty or --load".to_string());
+                }
+                Board::check_size(width, height, mines)?;
+                options.board = Some(BoardChoice::Custom { width, height, mines });
+            }
+            _ => return Err("a custom board needs --width, --height and --mines".to_string()),
+        }
+
+        if options.spectate.is_some() && (options.board.is_some() || options.broadcast.is_some() || options.api.is_some()) {
+            return Err("--spectate only watches, it can't be combined with a board, --broadcast or --api".to_string());
+        }
+
+        Ok(options)
+    }
+
+    // the board to open with, if one was asked for
+    pub fn build_board(&self) -> Result<Option<Board>, SaveError> {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let board = match &self.board {
+            None => return Ok(None),
+            Some(BoardChoice::Preset(difficulty)) => {
+                let (width, height, mines) = difficulty.dimensions();
+                Board::with_seed(width, height, mines, self.pure_random, seed)
+            }
+            Some(BoardChoice::Custom { width, height, mines }) => {
+                Board::with_seed(*width, *height, *mines, self.pure_random, seed)
+            }
+            Some(BoardChoice::Save(path)) => save::load_board(path)?,
+        };
+        Ok(Some(board))
+    }
+}
@@ -0,0 +1,72 @@
+// the game clock. It starts on the first reveal, stops on the win or loss and
+// doesn't count the time spent paused
+
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Default)]
+pub struct Clock {
+    // Some while the clock is ticking
+    running_since: Option<Instant>,
+    // time counted before the last pause
+    banked: Duration,
+    started: bool,
+    stopped: bool,
+}
+
+impl Clock {
+    /// A clock that hasn't started yet but already counts this much time.
+    pub fn with_elapsed(elapsed: Duration) -> Clock {
+        Clock {
+            banked: elapsed,
+            ..Clock::default()
+        }
+    }
+
+    /// Starts the clock, it only ever starts once.
+    pub fn start(&mut self) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        self.running_since = Some(Instant::now());
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.banked += since.elapsed();
+        }
+    }
+
+    /// Carries on after a pause, a clock that hasn't started or has stopped stays as it is.
+    pub fn resume(&mut self) {
+        if self.started && !self.stopped && self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// Stops the clock for good, the elapsed time is final from here on.
+    pub fn stop(&mut self) {
+        self.pause();
+        self.stopped = true;
+    }
+
+    /// Back to a clock that hasn't started yet.
+    pub fn reset(&mut self) {
+        *self = Clock::default();
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.banked + since.elapsed(),
+            None => self.banked,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.started && !self.stopped && self.running_since.is_none()
+    }
+}
@@ -0,0 +1,205 @@
+// the daily challenge, one board per calendar day (UTC) that is the same for everyone
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::objs::{Board, GameResult};
+use crate::stats::Outcome;
+use crate::storage;
+
+pub const DAILY_FILE: &str = "minesweeper-daily.json";
+
+pub const DAILY_WIDTH: usize = 16;
+pub const DAILY_HEIGHT: usize = 16;
+pub const DAILY_MINES: usize = 40;
+// revealed for the player before the daily starts, it's always an opening
+pub const DAILY_START: (usize, usize) = (DAILY_WIDTH / 2, DAILY_HEIGHT / 2);
+
+// keeps daily seeds apart from anything seeded with a small number by hand
+const DAILY_SALT: u64 = 0x6461_696c_7973_6565;
+
+// a calendar day, stored as days since 1970-01-01
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    days: i64,
+}
+
+impl Date {
+    pub fn today() -> Date {
+        Date {
+            days: (storage::now_timestamp() / 86400) as i64,
+        }
+    }
+
+    // converts a proleptic gregorian date, see http://howardhinnant.github.io/date_algorithms.html
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Date {
+        let year = if month <= 2 { year as i64 - 1 } else { year as i64 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month_index = if month > 2 { month as i64 - 3 } else { month as i64 + 9 };
+        let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        Date {
+            days: era * 146097 + day_of_era - 719468,
+        }
+    }
+
+    pub fn ymd(&self) -> (i32, u32, u32) {
+        let days = self.days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year as i32, month, day)
+    }
+
+    // 0 is monday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a thursday
+        (self.days + 3).rem_euclid(7) as u32
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date {
+            days: self.days + days,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        DAILY_SALT ^ self.days as u64
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    (Date::from_ymd(next_year, next_month, 1).days - Date::from_ymd(year, month, 1).days) as u32
+}
+
+// the board for a day, with the start tile already revealed
+pub fn daily_board(date: Date) -> Board {
+    let mut board = Board::with_safe_start(DAILY_WIDTH, DAILY_HEIGHT, DAILY_MINES, true, date.seed(), DAILY_START)
+        .expect("the daily size leaves room around the start");
+    board.reveal(DAILY_START.0, DAILY_START.1);
+    // the clock waits for the player's own first reveal
+    board.reset_clock();
+    board
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailyResult {
+    // None until the attempt is finished, so an abandoned attempt still counts
+    pub outcome: Option<Outcome>,
+    pub time_ms: Option<u64>,
+    pub bv3: u32,
+    pub clicks: u32,
+}
+
+// only the first attempt of every day is kept, keyed by the date as YYYY-MM-DD
+#[derive(Default, Serialize, Deserialize)]
+pub struct DailyResults {
+    results: BTreeMap<String, DailyResult>,
+}
+
+impl DailyResults {
+    pub fn get(&self, date: Date) -> Option<&DailyResult> {
+        self.results.get(&date.to_string())
+    }
+
+    // marks the day as attempted, true if this is the ranked attempt
+    pub fn start(&mut self, date: Date) -> bool {
+        if self.results.contains_key(&date.to_string()) {
+            return false;
+        }
+
+        self.results.insert(
+            date.to_string(),
+            DailyResult {
+                outcome: None,
+                time_ms: None,
+                bv3: 0,
+                clicks: 0,
+            },
+        );
+        true
+    }
+
+    // fills in the ranked attempt once the game is over
+    pub fn finish(&mut self, date: Date, board: &Board) {
+        let outcome = match board.get_result() {
+            GameResult::Won => Outcome::Won,
+            GameResult::Lost => Outcome::Lost,
+            GameResult::Continue => return,
+        };
+        if let Some(result) = self.results.get_mut(&date.to_string()) {
+            if result.outcome.is_none() {
+                result.outcome = Some(outcome);
+                result.time_ms = Some(board.get_elapsed_time().as_millis() as u64);
+                result.bv3 = board.get_3bv();
+                result.clicks = board.get_clicks();
+            }
+        }
+    }
+
+    pub fn load(path: &Path) -> io::Result<DailyResults> {
+        storage::load_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+}
+
+pub fn daily_path() -> PathBuf {
+    storage::data_path(DAILY_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_convert_both_ways() {
+        assert_eq!(Date::from_ymd(1970, 1, 1), Date { days: 0 });
+        for &(year, month, day) in &[(1970, 1, 1), (1969, 12, 31), (2000, 2, 29), (2024, 3, 1), (2100, 12, 31)] {
+            assert_eq!(Date::from_ymd(year, month, day).ymd(), (year, month, day));
+        }
+        let start = Date::from_ymd(1999, 12, 1);
+        for days in 0..800 {
+            let date = start.add_days(days);
+            let (year, month, day) = date.ymd();
+            assert_eq!(Date::from_ymd(year, month, day), date);
+        }
+    }
+
+    #[test]
+    fn weekdays_start_on_monday() {
+        // a thursday, a monday and a sunday
+        assert_eq!(Date::from_ymd(1970, 1, 1).weekday(), 3);
+        assert_eq!(Date::from_ymd(2024, 1, 1).weekday(), 0);
+        assert_eq!(Date::from_ymd(2023, 12, 31).weekday(), 6);
+    }
+
+    #[test]
+    fn months_have_the_right_length() {
+        assert_eq!(days_in_month(2024, 2), 29);
+        assert_eq!(days_in_month(2023, 2), 28);
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2023, 12), 31);
+    }
+}
@@ -0,0 +1,144 @@
+// labelled positions for training models. Games are played by the solver, and before every
+// move the visible board and the solver's mine chance for every tile are written out as one
+// sample, into a pair of NPY files that numpy can load as they are:
+//
+//   <prefix>-boards.npy   int8, (samples, height, width), encoded like gym.rs
+//   <prefix>-labels.npy   float32, (samples, height, width), 0 safe, 1 mine, otherwise the
+//                         chance of a mine, 0 for revealed tiles
+
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::gym;
+use crate::objs::{Board, GameResult};
+use crate::solver;
+
+// the NPY header is rewritten with the final sample count, so it gets a fixed size
+const NPY_HEADER_LEN: usize = 128;
+
+pub struct ExportConfig {
+    pub width: usize,
+    pub height: usize,
+    // mines per tile, games take turns going through the list
+    pub densities: Vec<f64>,
+    pub games: usize,
+    pub seed: u64,
+    pub pure_random: bool,
+}
+
+impl ExportConfig {
+    // mines for a density, rounded and kept within what the board can hold
+    pub fn mines_for(&self, density: f64) -> usize {
+        let tiles = self.width * self.height;
+        ((density * tiles as f64).round() as usize).clamp(1, Board::max_safe_start_mines(self.width, self.height).max(1))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ExportSummary {
+    pub games: usize,
+    pub wins: usize,
+    pub samples: usize,
+}
+
+// writes one NPY array row by row, without knowing up front how many rows there will be
+struct NpyWriter {
+    file: BufWriter<File>,
+    descr: &'static str,
+    row_shape: (usize, usize),
+    rows: usize,
+}
+
+impl NpyWriter {
+    fn create(path: &Path, descr: &'static str, row_shape: (usize, usize)) -> io::Result<NpyWriter> {
+        let mut writer = NpyWriter {
+            file: BufWriter::new(File::create(path)?),
+            descr,
+            row_shape,
+            rows: 0,
+        };
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let dict = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': ({}, {}, {}), }}",
+            self.descr, self.rows, self.row_shape.0, self.row_shape.1
+        );
+        // magic, version 1.0 and the header length, then the dict padded with spaces
+        let padding = NPY_HEADER_LEN - 10 - dict.len() - 1;
+        self.file.write_all(b"\x93NUMPY\x01\x00")?;
+        self.file.write_all(&((NPY_HEADER_LEN - 10) as u16).to_le_bytes())?;
+        writeln!(self.file, "{}{}", dict, " ".repeat(padding))
+    }
+
+    fn write_row(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.rows += 1;
+        self.file.write_all(bytes)
+    }
+
+    fn finish(mut self) -> io::Result<()> {
+        self.file.seek(SeekFrom::Start(0))?;
+        self.write_header()?;
+        self.file.flush()
+    }
+}
+
+pub fn export(config: &ExportConfig, prefix: &str) -> io::Result<ExportSummary> {
+    let mut boards = NpyWriter::create(Path::new(&format!("{}-boards.npy", prefix)), "|i1", (config.height, config.width))?;
+    let mut labels = NpyWriter::create(Path::new(&format!("{}-labels.npy", prefix)), "<f4", (config.height, config.width))?;
+    let mut summary = ExportSummary::default();
+
+    let mut observation = Vec::with_capacity(config.width * config.height);
+    let mut label_bytes = Vec::with_capacity(config.width * config.height * 4);
+    for game in 0..config.games {
+        let density = config.densities[game % config.densities.len()];
+        let seed = config.seed.wrapping_add(game as u64);
+        // the start tile is part of the seed too, so boards don't all open in one place
+        let mut rng = StdRng::seed_from_u64(seed);
+        let start = (rng.gen_range(0..config.width), rng.gen_range(0..config.height));
+        let mut board = Board::with_safe_start(config.width, config.height, config.mines_for(density), config.pure_random, seed, start)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        board.reveal(start.0, start.1);
+
+        while board.get_result() == GameResult::Continue {
+            let analysis = solver::analyze(board.view());
+
+            observation.clear();
+            gym::observe_into(board.view(), &mut observation);
+            boards.write_row(&observation.iter().map(|&value| value as u8).collect::<Vec<u8>>())?;
+            label_bytes.clear();
+            for &probability in analysis.probabilities() {
+                label_bytes.extend_from_slice(&(probability as f32).to_le_bytes());
+            }
+            labels.write_row(&label_bytes)?;
+            summary.samples += 1;
+
+            let safe = analysis.safe_tiles();
+            if safe.is_empty() {
+                match analysis.best_guess() {
+                    Some((x, y)) => board.reveal(x, y),
+                    None => break,
+                };
+            } else {
+                for (x, y) in safe {
+                    board.reveal(x, y);
+                }
+            }
+        }
+
+        summary.games += 1;
+        if board.get_result() == GameResult::Won {
+            summary.wins += 1;
+        }
+    }
+
+    boards.finish()?;
+    labels.finish()?;
+    Ok(summary)
+}
@@ -0,0 +1,41 @@
+// the preset boards offered by every frontend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    // width, height and mines
+    pub fn dimensions(&self) -> (usize, usize, usize) {
+        match self {
+            Difficulty::Easy => (10, 10, 5),
+            Difficulty::Medium => (15, 15, 30),
+            Difficulty::Hard => (25, 25, 50),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    // the preset a board of this size was made from, if any
+    pub fn matching(width: usize, height: usize, mines: usize) -> Option<Difficulty> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.dimensions() == (width, height, mines))
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(name))
+    }
+}
@@ -0,0 +1,164 @@
+// hand made boards. Mines and revealed tiles are painted onto a grid, the numbers follow
+// along, and the result is written as a normal save file that can be played with --load
+
+use std::path::Path;
+
+use crate::objs::{Board, GameResult, Tile, MAX_SIZE};
+use crate::save;
+use crate::solver;
+
+/// What the solver makes of a designed board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The board can be cleared from the start without a single guess.
+    NoGuess,
+    /// The solver got stuck with this many safe tiles still hidden.
+    NeedsGuess { hidden_safe: usize },
+    /// The start tile is a mine.
+    MineAtStart,
+}
+
+/// A tile of the board being designed, the designer gets to see everything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EditorTile {
+    pub is_mine: bool,
+    pub is_revealed: bool,
+    pub adjacent_mines: u8,
+}
+
+pub struct Editor {
+    width: usize,
+    height: usize,
+    // row by row, only the mines and revealed tiles matter
+    tiles: Vec<Tile>,
+    // rebuilt after every change so the numbers are always up to date
+    board: Board,
+}
+
+impl Editor {
+    pub fn new(width: usize, height: usize) -> Editor {
+        let width = width.clamp(3, MAX_SIZE);
+        let height = height.clamp(3, MAX_SIZE);
+        let mut editor = Editor {
+            width,
+            height,
+            tiles: vec![Tile::default(); width * height],
+            board: Board::default(),
+        };
+        editor.rebuild();
+        editor
+    }
+
+    fn rebuild(&mut self) {
+        self.board = Board::from_tiles(self.width, self.height, self.tiles.clone(), true, 0, 0, 1);
+    }
+
+    /// Changes the size, tiles inside both the old and the new size are kept.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let mut resized = Editor::new(width, height);
+        for y in 0..self.height.min(resized.height) {
+            for x in 0..self.width.min(resized.width) {
+                resized.tiles[y * resized.width + x] = self.tiles[y * self.width + x].clone();
+            }
+        }
+        resized.rebuild();
+        *self = resized;
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn mines(&self) -> usize {
+        self.tiles.iter().filter(|tile| tile.is_mine).count()
+    }
+
+    /// The tile with its number, mines and all.
+    pub fn tile(&self, x: usize, y: usize) -> EditorTile {
+        let tile = self.board.get_tile(x, y);
+        EditorTile {
+            is_mine: tile.is_mine,
+            is_revealed: tile.is_revealed,
+            adjacent_mines: tile.adjacent_mines,
+        }
+    }
+
+    /// A mine can't be revealed, so placing one hides the tile again.
+    pub fn toggle_mine(&mut self, x: usize, y: usize) {
+        let tile = &mut self.tiles[y * self.width + x];
+        tile.is_mine = !tile.is_mine;
+        tile.is_revealed = false;
+        self.rebuild();
+    }
+
+    /// Reveals or hides a safe tile, mines are left alone.
+    pub fn toggle_revealed(&mut self, x: usize, y: usize) {
+        let tile = &mut self.tiles[y * self.width + x];
+        if tile.is_mine {
+            return;
+        }
+        tile.is_revealed = !tile.is_revealed;
+        self.rebuild();
+    }
+
+    /// Checks that the board can be played, with a message saying why not.
+    pub fn check(&self) -> Result<(), String> {
+        Board::check_size(self.width, self.height, self.mines())?;
+        if self.tiles.iter().all(|tile| tile.is_mine || tile.is_revealed) {
+            return Err("every safe tile is already revealed".to_string());
+        }
+        Ok(())
+    }
+
+    /// The board as it would be played.
+    pub fn board(&self) -> Board {
+        self.board.clone()
+    }
+
+    /// Plays the board with the solver, only ever revealing tiles it knows are safe.
+    /// The start tile is revealed first, without one the board has to open from its revealed tiles.
+    pub fn solve(&self, start: Option<(usize, usize)>) -> Verdict {
+        let mut board = self.board();
+        if let Some((x, y)) = start {
+            if board.get_tile(x, y).is_mine {
+                return Verdict::MineAtStart;
+            }
+            board.reveal(x, y);
+        }
+
+        while board.get_result() == GameResult::Continue {
+            let analysis = solver::analyze(board.view());
+            let safe = analysis.safe_tiles();
+            // an estimate isn't proof that a tile is safe
+            if safe.is_empty() || !analysis.is_exact() {
+                break;
+            }
+            for (x, y) in safe {
+                board.reveal(x, y);
+            }
+        }
+
+        if board.get_result() == GameResult::Won {
+            return Verdict::NoGuess;
+        }
+        let mut hidden_safe = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tile = board.get_tile(x, y);
+                if !tile.is_mine && !tile.is_revealed {
+                    hidden_safe += 1;
+                }
+            }
+        }
+        Verdict::NeedsGuess { hidden_safe }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        self.check()?;
+        save::save_board(&self.board, path).map_err(|err| err.to_string())
+    }
+}
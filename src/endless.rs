@@ -0,0 +1,223 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::chunk::{chunk_of, index_of, ChunkMap, CHUNK_SIZE};
+use crate::objs::{reveal_area, Tile, TileGrid};
+use crate::view::Cell;
+
+// roughly the density of a medium board
+pub const DEFAULT_MINES_PER_CHUNK: u8 = 40;
+
+// an endless board, generated one chunk at a time as the player explores it.
+// every chunk is derived from the board seed and its own coordinates, so the
+// same seed always recreates the same region no matter the order it was visited in.
+pub struct EndlessBoard {
+    tiles: ChunkMap<Tile>,
+    seed: u64,
+    mines_per_chunk: u8,
+    flags: u32,
+    cleared: u64,
+    score: u64,
+}
+
+// splitmix64 finalizer, spreads nearby chunk coordinates over the whole seed space
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+impl EndlessBoard {
+    pub fn new(seed: u64, mines_per_chunk: u8) -> EndlessBoard {
+        let max_mines = (CHUNK_SIZE * CHUNK_SIZE / 2) as u8;
+        EndlessBoard {
+            tiles: ChunkMap::new(),
+            seed,
+            mines_per_chunk: mines_per_chunk.min(max_mines),
+            flags: 0,
+            cleared: 0,
+            score: 0,
+        }
+    }
+
+    // mine layout of a single chunk, only depends on the seed and the chunk position
+    fn chunk_mines(&self, chunk: (i32, i32)) -> Vec<bool> {
+        let (cx, cy) = chunk;
+        let position = ((cx as u32 as u64) << 32) | cy as u32 as u64;
+        let mut rng = StdRng::seed_from_u64(mix(self.seed ^ mix(position)));
+
+        let mut mines = vec![false; (CHUNK_SIZE * CHUNK_SIZE) as usize];
+        let mut mines_placed = 0;
+        while mines_placed < self.mines_per_chunk {
+            let x = cx * CHUNK_SIZE + rng.gen_range(0..CHUNK_SIZE);
+            let y = cy * CHUNK_SIZE + rng.gen_range(0..CHUNK_SIZE);
+
+            // keep the 3x3 area around the origin clear so the first click is an opening
+            if x.abs() <= 1 && y.abs() <= 1 {
+                continue;
+            }
+
+            if !mines[index_of(x, y)] {
+                mines[index_of(x, y)] = true;
+                mines_placed += 1;
+            }
+        }
+        mines
+    }
+
+    fn generate_chunk(&mut self, chunk: (i32, i32)) {
+        let (cx, cy) = chunk;
+
+        // the adjacent counts along the edges need the mines of the 8 neighbouring chunks too
+        let mut layouts = Vec::new();
+        for y_offset in -1..=1 {
+            for x_offset in -1..=1 {
+                layouts.push(self.chunk_mines((cx + x_offset, cy + y_offset)));
+            }
+        }
+        let is_mine = |x: i32, y: i32| {
+            let (x_chunk, y_chunk) = chunk_of(x, y);
+            let layout = ((y_chunk - cy + 1) * 3 + (x_chunk - cx + 1)) as usize;
+            layouts[layout][index_of(x, y)]
+        };
+
+        let mut tiles = Vec::new();
+        for y in cy * CHUNK_SIZE..(cy + 1) * CHUNK_SIZE {
+            for x in cx * CHUNK_SIZE..(cx + 1) * CHUNK_SIZE {
+                let mut adjacent_mines = 0;
+                for y_offset in -1..=1 {
+                    for x_offset in -1..=1 {
+                        if x_offset == 0 && y_offset == 0 {
+                            continue;
+                        }
+
+                        if is_mine(x + x_offset, y + y_offset) {
+                            adjacent_mines += 1;
+                        }
+                    }
+                }
+                tiles.push(Tile {
+                    is_mine: is_mine(x, y),
+                    adjacent_mines,
+                    ..Default::default()
+                });
+            }
+        }
+        self.tiles.insert_chunk(chunk, tiles);
+    }
+
+    fn tile_mut(&mut self, x: i32, y: i32) -> &mut Tile {
+        let chunk = chunk_of(x, y);
+        if !self.tiles.has_chunk(chunk) {
+            self.generate_chunk(chunk);
+        }
+        self.tiles.get_mut(x, y).unwrap()
+    }
+
+    pub fn select_tile(&mut self, x: i32, y: i32) -> bool {
+        let tile = self.tile_mut(x, y);
+        if tile.is_flagged || tile.is_revealed {
+            return false;
+        }
+        if tile.is_mine {
+            tile.is_revealed = true;
+            return true;
+        }
+
+        let revealed = reveal_area(self, x, y).len() as u64;
+        self.cleared += revealed;
+        self.score += revealed;
+        false
+    }
+
+    pub fn flag_tile(&mut self, x: i32, y: i32) {
+        match self.tile_mut(x, y).toggle_flag() {
+            Some(true) => self.flags += 1,
+            Some(false) => self.flags -= 1,
+            None => {}
+        }
+    }
+
+    pub fn on_lost(&mut self) {
+        // only the explored part of the board can be shown
+        let bonus: u32 = self.tiles.values_mut().map(Tile::show_if_mine).sum();
+        self.score += bonus as u64;
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_flags(&self) -> u32 {
+        self.flags
+    }
+
+    pub fn get_cleared(&self) -> u64 {
+        self.cleared
+    }
+
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    pub fn get_chunks_explored(&self) -> usize {
+        self.tiles.chunk_count()
+    }
+
+    // generates the chunk if nobody looked at it yet
+    pub fn get_cell(&mut self, x: i32, y: i32) -> Cell {
+        Cell::of(self.tile_mut(x, y))
+    }
+}
+
+impl TileGrid for EndlessBoard {
+    fn tile_at(&mut self, x: i32, y: i32) -> &mut Tile {
+        self.tile_mut(x, y)
+    }
+
+    // the board goes on forever, every tile has all eight
+    fn neighbours_of(&self, x: i32, y: i32) -> Vec<(i32, i32)> {
+        let mut neighbours = Vec::with_capacity(8);
+        for y_offset in -1..=1 {
+            for x_offset in -1..=1 {
+                if x_offset != 0 || y_offset != 0 {
+                    neighbours.push((x + x_offset, y + y_offset));
+                }
+            }
+        }
+        neighbours
+    }
+}
+
+impl Default for EndlessBoard {
+    fn default() -> Self {
+        Self::new(0, DEFAULT_MINES_PER_CHUNK)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_origin_is_an_opening() {
+        let mut board = EndlessBoard::new(7, DEFAULT_MINES_PER_CHUNK);
+        assert!(!board.select_tile(0, 0));
+        assert!(board.get_cleared() > 1);
+        assert_eq!(board.score(), board.get_cleared());
+        assert_eq!(board.get_cell(0, 0), Cell::Number(0));
+    }
+
+    #[test]
+    fn flags_only_go_on_hidden_tiles() {
+        let mut board = EndlessBoard::new(7, DEFAULT_MINES_PER_CHUNK);
+        board.select_tile(0, 0);
+        board.flag_tile(0, 0);
+        assert_eq!(board.get_flags(), 0);
+        board.flag_tile(100, 100);
+        assert_eq!(board.get_flags(), 1);
+        board.flag_tile(100, 100);
+        assert_eq!(board.get_flags(), 0);
+    }
+}
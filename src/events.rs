@@ -0,0 +1,44 @@
+// what happens on a board as it is played, for anything that wants to follow along:
+// sound, animation, logging or sending moves over the network. Spectating is built on them,
+// see spectate.rs
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BoardEvent {
+    // a safe tile was revealed, by a click or by an opening spreading
+    TileRevealed { x: usize, y: usize, adjacent_mines: u8 },
+    // a flag was placed, or taken away when `flagged` is false
+    Flagged { x: usize, y: usize, flagged: bool },
+    // a mine was revealed, the game is lost unless there were lives left
+    Exploded { x: usize, y: usize },
+    Won,
+}
+
+type Listener = Box<dyn FnMut(&BoardEvent) + Send>;
+
+// the listeners of one board. A copy of a board starts without any, so cloning a board to
+// look at it or hand it elsewhere doesn't fire events twice.
+#[derive(Default)]
+pub(crate) struct Listeners {
+    listeners: Vec<Listener>,
+}
+
+impl Listeners {
+    pub(crate) fn add(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+
+    pub(crate) fn emit(&mut self, event: BoardEvent) {
+        for listener in &mut self.listeners {
+            listener(&event);
+        }
+    }
+}
+
+impl Clone for Listeners {
+    fn clone(&self) -> Self {
+        Listeners::default()
+    }
+}
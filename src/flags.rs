@@ -0,0 +1,106 @@
+// Minesweeper Flags, two players take turns on one shared board and race to find the mines.
+// finding a mine claims it and gives another turn, revealing a safe tile passes the turn,
+// and whoever claims more than half of the mines wins.
+
+use std::collections::HashMap;
+
+use crate::objs::Board;
+
+// the usual Flags board, an odd mine count means there is always a winner
+pub const FLAGS_WIDTH: usize = 16;
+pub const FLAGS_HEIGHT: usize = 16;
+pub const FLAGS_MINES: usize = 51;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlagsMove {
+    // a mine was found, the same player goes again
+    Claimed,
+    // a safe tile was revealed, the other player's turn
+    Missed,
+    // the tile was already revealed or the game is over
+    Ignored,
+}
+
+pub struct FlagsGame {
+    board: Board,
+    names: [String; 2],
+    scores: [u32; 2],
+    current: usize,
+    // which player claimed each found mine
+    owners: HashMap<(usize, usize), usize>,
+    finished: bool,
+}
+
+impl FlagsGame {
+    pub fn new(names: [String; 2], width: usize, height: usize, mines: usize, pure_random: bool, seed: u64) -> FlagsGame {
+        FlagsGame {
+            board: Board::with_seed(width, height, mines, pure_random, seed),
+            names,
+            scores: [0, 0],
+            current: 0,
+            owners: HashMap::new(),
+            finished: false,
+        }
+    }
+
+    pub fn reveal(&mut self, x: usize, y: usize) -> FlagsMove {
+        if self.finished {
+            return FlagsMove::Ignored;
+        }
+
+        match self.board.claim_tile(x, y) {
+            Some(true) => {
+                self.owners.insert((x, y), self.current);
+                self.scores[self.current] += 1;
+                let claimed = self.scores[0] + self.scores[1];
+                if self.scores[self.current] >= self.mines_to_win() || claimed == self.board.get_mines() as u32 {
+                    self.finished = true;
+                }
+                FlagsMove::Claimed
+            }
+            Some(false) => {
+                self.current = 1 - self.current;
+                FlagsMove::Missed
+            }
+            None => FlagsMove::Ignored,
+        }
+    }
+
+    // more than half of the mines
+    pub fn mines_to_win(&self) -> u32 {
+        self.board.get_mines() as u32 / 2 + 1
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn names(&self) -> &[String; 2] {
+        &self.names
+    }
+
+    pub fn scores(&self) -> [u32; 2] {
+        self.scores
+    }
+
+    // whose turn it is, 0 or 1
+    pub fn current_player(&self) -> usize {
+        self.current
+    }
+
+    pub fn owner(&self, x: usize, y: usize) -> Option<usize> {
+        self.owners.get(&(x, y)).copied()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    // None for a tie (only possible with an even mine count) or while still playing
+    pub fn winner(&self) -> Option<usize> {
+        if !self.finished || self.scores[0] == self.scores[1] {
+            return None;
+        }
+        Some(if self.scores[0] > self.scores[1] { 0 } else { 1 })
+    }
+}
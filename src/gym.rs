@@ -0,0 +1,404 @@
+// a gym style environment for training agents against the real rules.
+//
+// observations are one i8 per tile, row by row, so every observation of a board size has
+// the same shape: 0-8 for revealed numbers, UNKNOWN, FLAGGED, and MINE for the mines that
+// only show up once a game is lost. Agents only ever see the player view.
+
+use crate::objs::{Board, GameResult};
+use crate::view::{Cell, PlayerView};
+
+pub const UNKNOWN: i8 = -1;
+pub const FLAGGED: i8 = -2;
+pub const MINE: i8 = -3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Reveal { x: usize, y: usize },
+    Flag { x: usize, y: usize },
+    Chord { x: usize, y: usize },
+}
+
+impl Action {
+    // a flat action space of 3 * width * height: all reveals, then flags, then chords
+    pub fn from_index(index: usize, width: usize, height: usize) -> Option<Action> {
+        let tiles = width * height;
+        if index >= tiles * 3 {
+            return None;
+        }
+        let (x, y) = (index % tiles % width, index % tiles / width);
+        Some(match index / tiles {
+            0 => Action::Reveal { x, y },
+            1 => Action::Flag { x, y },
+            _ => Action::Chord { x, y },
+        })
+    }
+
+    pub fn position(&self) -> (usize, usize) {
+        match *self {
+            Action::Reveal { x, y } | Action::Flag { x, y } | Action::Chord { x, y } => (x, y),
+        }
+    }
+}
+
+// what every step is worth, tune these to shape an agent's behaviour
+#[derive(Clone, Copy, Debug)]
+pub struct Rewards {
+    pub win: f32,
+    pub loss: f32,
+    // for every safe tile a move revealed
+    pub per_tile: f32,
+    // for moves that changed nothing, like revealing a revealed tile
+    pub no_progress: f32,
+    // for revealing a tile with no revealed neighbour once the game is going
+    pub guess: f32,
+    // for placing or removing a flag
+    pub flag: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Rewards {
+            win: 1.0,
+            loss: -1.0,
+            per_tile: 0.01,
+            no_progress: -0.05,
+            guess: 0.0,
+            flag: 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EnvConfig {
+    pub width: usize,
+    pub height: usize,
+    pub mines: usize,
+    pub pure_random: bool,
+    // deal the mines after the first reveal, so it always opens up the board
+    pub safe_first_move: bool,
+    pub rewards: Rewards,
+}
+
+impl EnvConfig {
+    pub fn new(width: usize, height: usize, mines: usize) -> EnvConfig {
+        EnvConfig {
+            width,
+            height,
+            mines,
+            pure_random: true,
+            safe_first_move: true,
+            rewards: Rewards::default(),
+        }
+    }
+
+    pub fn observation_len(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn action_count(&self) -> usize {
+        self.observation_len() * 3
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub observation: Vec<i8>,
+    pub reward: f32,
+    pub done: bool,
+    pub result: GameResult,
+}
+
+pub fn encode(cell: Cell) -> i8 {
+    match cell {
+        Cell::Hidden => UNKNOWN,
+        Cell::Flagged => FLAGGED,
+        Cell::Number(adjacent_mines) => adjacent_mines as i8,
+        Cell::Mine => MINE,
+    }
+}
+
+// appends the observation of a board, row by row
+pub fn observe_into(view: PlayerView, out: &mut Vec<i8>) {
+    for y in 0..view.height() {
+        for x in 0..view.width() {
+            out.push(encode(view.cell(x, y)));
+        }
+    }
+}
+
+pub struct Env {
+    config: EnvConfig,
+    board: Board,
+    seed: u64,
+    // false until the first reveal when the mines wait for it
+    dealt: bool,
+}
+
+impl Env {
+    pub fn new(config: EnvConfig) -> Result<Env, String> {
+        if config.safe_first_move {
+            Board::check_safe_start(config.width, config.height, config.mines)?;
+        } else {
+            Board::check_size(config.width, config.height, config.mines)?;
+        }
+        let mut env = Env {
+            config,
+            board: Board::default(),
+            seed: 0,
+            dealt: false,
+        };
+        env.reset(0);
+        Ok(env)
+    }
+
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    pub fn board(&self) -> PlayerView<'_> {
+        self.board.view()
+    }
+
+    pub fn reset(&mut self, seed: u64) -> Vec<i8> {
+        let config = &self.config;
+        self.seed = seed;
+        self.board = Board::with_seed(config.width, config.height, config.mines, config.pure_random, seed);
+        self.dealt = !config.safe_first_move;
+        self.observation()
+    }
+
+    pub fn observation(&self) -> Vec<i8> {
+        let mut observation = Vec::with_capacity(self.config.observation_len());
+        observe_into(self.board.view(), &mut observation);
+        observation
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.board.get_result() != GameResult::Continue
+    }
+
+    pub fn step(&mut self, action: Action) -> Step {
+        let reward = self.apply(action);
+        Step {
+            observation: self.observation(),
+            reward,
+            done: self.is_done(),
+            result: self.board.get_result(),
+        }
+    }
+
+    // deals the mines away from the first reveal, the flags placed so far stay where they are
+    fn deal_around(&mut self, x: usize, y: usize) {
+        let config = &self.config;
+        let mut board = Board::with_safe_start(config.width, config.height, config.mines, config.pure_random, self.seed, (x, y))
+            .expect("the size is checked in Env::new");
+        let view = self.board.view();
+        for flag_y in 0..config.height {
+            for flag_x in 0..config.width {
+                if view.cell(flag_x, flag_y) == Cell::Flagged {
+                    board.flag(flag_x, flag_y);
+                }
+            }
+        }
+        self.board = board;
+        self.dealt = true;
+    }
+
+    // makes the move and works out its reward, without building an observation
+    fn apply(&mut self, action: Action) -> f32 {
+        if self.is_done() {
+            return 0.0;
+        }
+        let rewards = self.config.rewards;
+        let (x, y) = action.position();
+        if x >= self.config.width || y >= self.config.height {
+            return rewards.no_progress;
+        }
+
+        // a reveal on a flag does nothing, so it doesn't deal the mines either
+        if let Action::Reveal { .. } = action {
+            if !self.dealt && self.board.view().cell(x, y) == Cell::Hidden {
+                self.deal_around(x, y);
+            }
+        }
+
+        let view = self.board.view();
+        let before = view.cell(x, y);
+        let cleared = view.score();
+        let guess = matches!(action, Action::Reveal { .. })
+            && before == Cell::Hidden
+            && cleared > 0
+            && view.neighbours(x, y).iter().all(|&(nx, ny)| !view.cell(nx, ny).is_revealed());
+
+        let result = match action {
+            Action::Reveal { x, y } => self.board.reveal(x, y),
+            Action::Flag { x, y } => self.board.flag(x, y),
+            Action::Chord { x, y } => self.board.chord(x, y),
+        };
+
+        match result {
+            GameResult::Won => rewards.win,
+            GameResult::Lost => rewards.loss,
+            GameResult::Continue => {
+                let view = self.board.view();
+                let revealed = view.score() - cleared;
+                let changed = revealed > 0 || view.cell(x, y) != before;
+                let mut reward = revealed as f32 * rewards.per_tile;
+                if !changed {
+                    reward += rewards.no_progress;
+                }
+                if guess {
+                    reward += rewards.guess;
+                }
+                if changed && matches!(action, Action::Flag { .. }) {
+                    reward += rewards.flag;
+                }
+                reward
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchStep {
+    // every board's observation one after the other, count * width * height values
+    pub observations: Vec<i8>,
+    pub rewards: Vec<f32>,
+    pub dones: Vec<bool>,
+    pub results: Vec<GameResult>,
+}
+
+// many boards stepped together. A board that finishes is reset with the next seed straight
+// away, its done flag and result say how the finished game went.
+pub struct VecEnv {
+    envs: Vec<Env>,
+    next_seed: u64,
+}
+
+impl VecEnv {
+    pub fn new(config: EnvConfig, count: usize) -> Result<VecEnv, String> {
+        let envs = (0..count).map(|_| Env::new(config)).collect::<Result<_, _>>()?;
+        Ok(VecEnv { envs, next_seed: 0 })
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    pub fn envs(&self) -> &[Env] {
+        &self.envs
+    }
+
+    // board i gets seed + i, later resets carry on from there
+    pub fn reset(&mut self, seed: u64) -> Vec<i8> {
+        self.next_seed = seed;
+        let mut observations = Vec::new();
+        for env in &mut self.envs {
+            env.reset(self.next_seed);
+            observe_into(env.board(), &mut observations);
+            self.next_seed = self.next_seed.wrapping_add(1);
+        }
+        observations
+    }
+
+    // one action per board, in the same order as the boards
+    pub fn step(&mut self, actions: &[Action]) -> Result<BatchStep, String> {
+        if actions.len() != self.envs.len() {
+            return Err(format!("expected {} actions, got {}", self.envs.len(), actions.len()));
+        }
+
+        let mut batch = BatchStep {
+            observations: Vec::new(),
+            rewards: Vec::with_capacity(actions.len()),
+            dones: Vec::with_capacity(actions.len()),
+            results: Vec::with_capacity(actions.len()),
+        };
+        for (env, &action) in self.envs.iter_mut().zip(actions) {
+            batch.rewards.push(env.apply(action));
+            batch.dones.push(env.is_done());
+            batch.results.push(env.board.get_result());
+            if env.is_done() {
+                env.reset(self.next_seed);
+                self.next_seed = self.next_seed.wrapping_add(1);
+            }
+            observe_into(env.board(), &mut batch.observations);
+        }
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EnvConfig {
+        EnvConfig::new(9, 9, 10)
+    }
+
+    #[test]
+    fn reset_hides_the_whole_board() {
+        let mut env = Env::new(config()).unwrap();
+        env.step(Action::Reveal { x: 4, y: 4 });
+        let observation = env.reset(3);
+        assert_eq!(observation, vec![UNKNOWN; config().observation_len()]);
+        assert!(!env.is_done());
+    }
+
+    #[test]
+    fn the_first_reveal_opens_up_the_board_and_keeps_the_flags() {
+        let mut env = Env::new(config()).unwrap();
+        env.step(Action::Flag { x: 0, y: 0 });
+        let step = env.step(Action::Reveal { x: 0, y: 0 });
+        assert_eq!(step.observation[0], FLAGGED);
+        assert_eq!(step.reward, config().rewards.no_progress);
+
+        let step = env.step(Action::Reveal { x: 4, y: 4 });
+        assert_eq!(step.observation[0], FLAGGED);
+        assert_eq!(step.observation[4 * 9 + 4], 0);
+        let revealed = step.observation.iter().filter(|&&cell| cell >= 0).count();
+        if !step.done {
+            assert_eq!(step.reward, revealed as f32 * config().rewards.per_tile);
+        }
+    }
+
+    #[test]
+    fn moves_that_change_nothing_cost_a_little() {
+        let mut env = Env::new(config()).unwrap();
+        let step = env.step(Action::Reveal { x: 4, y: 4 });
+        if step.done {
+            return;
+        }
+        let step = env.step(Action::Reveal { x: 4, y: 4 });
+        assert_eq!(step.reward, config().rewards.no_progress);
+        let step = env.step(Action::Flag { x: 9, y: 0 });
+        assert_eq!(step.reward, config().rewards.no_progress);
+    }
+
+    #[test]
+    fn batches_step_every_board_and_reset_the_finished_ones() {
+        let config = EnvConfig { safe_first_move: false, ..EnvConfig::new(3, 3, 8) };
+        let mut envs = VecEnv::new(config, 3).unwrap();
+        assert_eq!(envs.reset(10).len(), 3 * 9);
+        assert!(envs.step(&[Action::Reveal { x: 0, y: 0 }]).is_err());
+
+        // one safe tile among eight mines, almost every reveal ends the game
+        let actions = [Action::Reveal { x: 1, y: 1 }; 3];
+        let batch = envs.step(&actions).unwrap();
+        assert_eq!(batch.observations.len(), 3 * 9);
+        assert_eq!(batch.rewards.len(), 3);
+        for index in 0..3 {
+            assert!(batch.dones[index]);
+            let reward = match batch.results[index] {
+                GameResult::Won => config.rewards.win,
+                _ => config.rewards.loss,
+            };
+            assert_eq!(batch.rewards[index], reward);
+            // finished boards start over before their observation is taken
+            assert!(batch.observations[index * 9..(index + 1) * 9].iter().all(|&cell| cell == UNKNOWN));
+        }
+    }
+}
@@ -0,0 +1,162 @@
+// best winning times, a top 10 for every board size
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::storage;
+
+pub const HIGHSCORES_FILE: &str = "minesweeper-highscores.json";
+
+// how many times every table keeps
+pub const TABLE_SIZE: usize = 10;
+
+// boards are grouped by their exact size, so presets and custom boards work the same way
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BoardSize {
+    pub width: u8,
+    pub height: u8,
+    pub mines: u8,
+}
+
+impl BoardSize {
+    pub fn of_difficulty(difficulty: Difficulty) -> BoardSize {
+        let (width, height, mines) = difficulty.dimensions();
+        BoardSize {
+            width: width as u8,
+            height: height as u8,
+            mines: mines as u8,
+        }
+    }
+
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        Difficulty::matching(self.width as usize, self.height as usize, self.mines as usize)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HighScore {
+    pub size: BoardSize,
+    pub name: String,
+    pub time_ms: u64,
+    // unix seconds
+    pub achieved_at: u64,
+}
+
+impl HighScore {
+    pub fn time(&self) -> Duration {
+        Duration::from_millis(self.time_ms)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct HighScores {
+    scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    // fastest first
+    pub fn table(&self, size: BoardSize) -> Vec<&HighScore> {
+        let mut table: Vec<&HighScore> = self.scores.iter().filter(|score| score.size == size).collect();
+        table.sort_by_key(|score| (score.time_ms, score.achieved_at));
+        table
+    }
+
+    // every board size that has at least one score and isn't a preset
+    pub fn custom_sizes(&self) -> Vec<BoardSize> {
+        let mut sizes: Vec<BoardSize> = self
+            .scores
+            .iter()
+            .map(|score| score.size)
+            .filter(|size| size.difficulty().is_none())
+            .collect();
+        sizes.sort();
+        sizes.dedup();
+        sizes
+    }
+
+    pub fn qualifies(&self, size: BoardSize, time: Duration) -> bool {
+        let table = self.table(size);
+        table.len() < TABLE_SIZE || time.as_millis() < table[TABLE_SIZE - 1].time_ms as u128
+    }
+
+    // adds the score if it makes the table, and drops whatever falls off the end
+    pub fn add(&mut self, size: BoardSize, name: &str, time: Duration) -> bool {
+        if !self.qualifies(size, time) {
+            return false;
+        }
+
+        self.scores.push(HighScore {
+            size,
+            name: name.trim().to_string(),
+            time_ms: time.as_millis() as u64,
+            achieved_at: storage::now_timestamp(),
+        });
+
+        let table = self.table(size);
+        if table.len() > TABLE_SIZE {
+            let slowest = (table[TABLE_SIZE].time_ms, table[TABLE_SIZE].achieved_at);
+            self.scores
+                .retain(|score| score.size != size || (score.time_ms, score.achieved_at) < slowest);
+        }
+        true
+    }
+
+    pub fn reset(&mut self, size: BoardSize) {
+        self.scores.retain(|score| score.size != size);
+    }
+
+    pub fn reset_custom(&mut self) {
+        self.scores.retain(|score| score.size.difficulty().is_some());
+    }
+
+    pub fn load(path: &Path) -> io::Result<HighScores> {
+        storage::load_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+}
+
+pub fn highscores_path() -> PathBuf {
+    storage::data_path(HIGHSCORES_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_keep_the_ten_fastest() {
+        let size = BoardSize::of_difficulty(Difficulty::Easy);
+        let mut highscores = HighScores::default();
+        for secs in 1..=TABLE_SIZE as u64 {
+            assert!(highscores.add(size, "slow", Duration::from_secs(secs * 10)));
+        }
+        assert!(!highscores.qualifies(size, Duration::from_secs(200)));
+        assert!(!highscores.add(size, "too slow", Duration::from_secs(200)));
+
+        assert!(highscores.add(size, " fast ", Duration::from_secs(5)));
+        let table = highscores.table(size);
+        assert_eq!(table.len(), TABLE_SIZE);
+        assert_eq!(table[0].name, "fast");
+        assert_eq!(table[TABLE_SIZE - 1].time(), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn tables_are_kept_per_size() {
+        let easy = BoardSize::of_difficulty(Difficulty::Easy);
+        let custom = BoardSize { width: 5, height: 5, mines: 3 };
+        let mut highscores = HighScores::default();
+        for secs in 1..=TABLE_SIZE as u64 {
+            highscores.add(easy, "easy", Duration::from_secs(secs));
+        }
+        assert!(highscores.add(custom, "custom", Duration::from_secs(100)));
+        assert_eq!(highscores.table(easy).len(), TABLE_SIZE);
+        assert_eq!(highscores.custom_sizes(), vec![custom]);
+    }
+}
@@ -0,0 +1,147 @@
+// hot seat races, two players take turns on the same mine layout and compare how they did
+
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use crate::objs::{Board, GameResult};
+
+// how one player's board went
+#[derive(Clone, Debug)]
+pub struct PlayerRun {
+    pub result: GameResult,
+    pub time: Duration,
+    pub bv3: u32,
+    pub clicks: u32,
+    // safe tiles revealed, decides between two lost runs
+    pub cleared: u32,
+}
+
+impl PlayerRun {
+    pub fn from_board(board: &Board) -> PlayerRun {
+        PlayerRun {
+            result: board.get_result(),
+            time: board.get_elapsed_time(),
+            bv3: board.get_3bv(),
+            clicks: board.get_clicks(),
+            cleared: board.get_cleared(),
+        }
+    }
+
+    // only meaningful for a won board, a lost one never cleared all of its 3BV
+    pub fn bv3_per_second(&self) -> f64 {
+        if self.result != GameResult::Won || self.time.is_zero() {
+            return 0.0;
+        }
+        self.bv3 as f64 / self.time.as_secs_f64()
+    }
+
+    // Greater is the better run: a win beats a loss, then the faster win
+    // or the loss that cleared more of the board
+    fn compare(&self, other: &PlayerRun) -> Ordering {
+        let won = self.result == GameResult::Won;
+        let other_won = other.result == GameResult::Won;
+        match (won, other_won) {
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (true, true) => other.time.cmp(&self.time),
+            (false, false) => self.cleared.cmp(&other.cleared),
+        }
+    }
+}
+
+pub struct HotSeat {
+    names: [String; 2],
+    width: usize,
+    height: usize,
+    mines: usize,
+    pure_random: bool,
+    seed: u64,
+    runs: Vec<PlayerRun>,
+}
+
+impl HotSeat {
+    pub fn new(names: [String; 2], width: usize, height: usize, mines: usize, pure_random: bool, seed: u64) -> HotSeat {
+        HotSeat {
+            names,
+            width,
+            height,
+            mines,
+            pure_random,
+            seed,
+            runs: Vec::new(),
+        }
+    }
+
+    // same names and board size, new layout
+    pub fn rematch(&self, seed: u64) -> HotSeat {
+        HotSeat::new(self.names.clone(), self.width, self.height, self.mines, self.pure_random, seed)
+    }
+
+    // a fresh board for whoever plays next, every player gets the same layout
+    pub fn board(&self) -> Board {
+        Board::with_seed(self.width, self.height, self.mines, self.pure_random, self.seed)
+    }
+
+    pub fn names(&self) -> &[String; 2] {
+        &self.names
+    }
+
+    // None once both players had their turn
+    pub fn current_player(&self) -> Option<&str> {
+        self.names.get(self.runs.len()).map(String::as_str)
+    }
+
+    pub fn record_run(&mut self, board: &Board) {
+        if !self.is_finished() && board.get_result() != GameResult::Continue {
+            self.runs.push(PlayerRun::from_board(board));
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.runs.len() == self.names.len()
+    }
+
+    pub fn runs(&self) -> &[PlayerRun] {
+        &self.runs
+    }
+
+    // index of the better player, None for a tie or while the race is still going
+    pub fn winner(&self) -> Option<usize> {
+        if !self.is_finished() {
+            return None;
+        }
+        match self.runs[0].compare(&self.runs[1]) {
+            Ordering::Greater => Some(0),
+            Ordering::Less => Some(1),
+            Ordering::Equal => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_dont_count_as_cleared_tiles() {
+        let mut board = Board::with_seed(9, 9, 10, true, 5);
+        let mines: Vec<(usize, usize)> = (0..81)
+            .map(|index| (index % 9, index / 9))
+            .filter(|&(x, y)| board.get_tile(x, y).is_mine())
+            .collect();
+        let (x, y) = (0..81)
+            .map(|index| (index % 9, index / 9))
+            .find(|&(x, y)| !board.get_tile(x, y).is_mine() && board.get_tile(x, y).get_adjacent_mines() > 0)
+            .unwrap();
+        board.reveal(x, y);
+        for &(x, y) in &mines[1..] {
+            board.flag(x, y);
+        }
+        board.reveal(mines[0].0, mines[0].1);
+        assert_eq!(board.get_result(), GameResult::Lost);
+
+        let run = PlayerRun::from_board(&board);
+        assert_eq!(run.cleared, 1);
+        assert!(board.get_score() > 1);
+    }
+}
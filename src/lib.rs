@@ -0,0 +1,47 @@
+//! Minesweeper game engine.
+//!
+//! The rules live in [`Board`] and [`EndlessBoard`] and have no GUI dependencies.
+//! Frontends read a board through [`PlayerView`], which keeps the mines hidden until the game is over.
+//! The eframe frontend is only built with the `gui` feature, which is on by default.
+
+pub mod api;
+pub mod bench;
+pub mod campaign;
+pub mod chunk;
+pub mod clock;
+pub mod cli;
+pub mod daily;
+pub mod dataset;
+pub mod difficulty;
+pub mod editor;
+pub mod endless;
+pub mod events;
+pub mod flags;
+pub mod gym;
+pub mod highscores;
+pub mod hotseat;
+pub mod marathon;
+pub mod net;
+pub mod objs;
+pub mod palette;
+pub mod protocol;
+pub mod save;
+pub mod solver;
+pub mod spectate;
+pub mod state;
+pub mod stats;
+pub mod storage;
+pub mod timeattack;
+pub mod view;
+
+#[cfg(feature = "gui")]
+pub mod minesweeper;
+#[cfg(feature = "gui")]
+pub mod texturedb;
+
+pub use difficulty::Difficulty;
+pub use endless::EndlessBoard;
+pub use events::BoardEvent;
+pub use objs::{Board, GameResult, Tile};
+pub use state::GameState;
+pub use view::{Cell, PlayerView};
@@ -1,26 +1,66 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-use eframe::egui;
 use eframe::run_native;
-use egui::Color32;
-use egui::Vec2;
-use objs::Board;
-use minesweeper::Minesweeper;
-use minesweeper::CustomBoard;
-
-mod objs;
-mod texturedb;
-mod minesweeper;
+use minesweeper::cli::{LaunchOptions, USAGE};
+use minesweeper::minesweeper::Minesweeper;
 
 fn main() {
-    let mut options = eframe::NativeOptions::default();
+    let launch = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(launch) => launch,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if launch.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let mut app = match &launch.assets {
+        Some(dir) => Minesweeper::with_assets(dir),
+        None => Minesweeper::default(),
+    };
+    app.pure_random = launch.pure_random;
+
+    if let Some(address) = &launch.broadcast {
+        if let Err(err) = app.start_broadcast(address) {
+            eprintln!("could not broadcast on {}: {}", address, err);
+            std::process::exit(1);
+        }
+    }
+    if let Some(address) = &launch.api {
+        if let Err(err) = app.start_api(address) {
+            eprintln!("could not serve the API on {}: {}", address, err);
+            std::process::exit(1);
+        }
+    }
+    if let Some(address) = &launch.spectate {
+        if let Err(err) = app.spectate(address, launch.delay) {
+            eprintln!("could not connect to {}: {}", address, err);
+            std::process::exit(1);
+        }
+    }
+
+    // skip the menu when a board was given
+    match launch.build_board() {
+        Ok(Some(board)) => app.start_game(board),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
 
-    options.initial_window_size = Some(Vec2::new(300.0, 300.0));
-    options.resizable = false;
+    let options = eframe::NativeOptions {
+        initial_window_size: Some(app.get_window_size()),
+        resizable: false,
+        ..Default::default()
+    };
 
     run_native(
         "Minesweeper",
         options,
-        Box::new(|_ctx| Box::new(Minesweeper::default())),
+        Box::new(|_ctx| Box::new(app)),
     );
 }
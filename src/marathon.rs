@@ -0,0 +1,155 @@
+// marathon, one board after another with a few more mines every time. The score carries over
+// from board to board and the session is over at the first loss
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::objs::{Board, GameResult};
+use crate::storage;
+
+pub const MARATHON_FILE: &str = "minesweeper-marathon.json";
+
+pub const MARATHON_WIDTH: usize = 12;
+pub const MARATHON_HEIGHT: usize = 12;
+pub const START_MINES: usize = 12;
+// added to every board after the first
+pub const MINES_PER_BOARD: usize = 3;
+// past this the boards are mostly guessing, so the density stops going up
+pub const MAX_DENSITY: f64 = 0.3;
+
+pub struct Marathon {
+    pure_random: bool,
+    boards_cleared: u32,
+    // score of the boards already finished
+    carried_score: u32,
+    total_time: Duration,
+    peak_density: f64,
+    finished: bool,
+}
+
+impl Marathon {
+    pub fn new(pure_random: bool) -> Marathon {
+        Marathon {
+            pure_random,
+            boards_cleared: 0,
+            carried_score: 0,
+            total_time: Duration::ZERO,
+            peak_density: 0.0,
+            finished: false,
+        }
+    }
+
+    pub fn mines(&self) -> usize {
+        let tiles = MARATHON_WIDTH * MARATHON_HEIGHT;
+        let max_mines = (tiles as f64 * MAX_DENSITY) as usize;
+        (START_MINES + MINES_PER_BOARD * self.boards_cleared as usize).min(max_mines)
+    }
+
+    pub fn density(&self) -> f64 {
+        self.mines() as f64 / (MARATHON_WIDTH * MARATHON_HEIGHT) as f64
+    }
+
+    // the board to play now
+    pub fn board(&self) -> Board {
+        Board::new(MARATHON_WIDTH, MARATHON_HEIGHT, self.mines(), self.pure_random)
+    }
+
+    // adds a finished board to the session, and gives the next board after a win
+    pub fn finish_board(&mut self, board: &Board) -> Option<Board> {
+        if self.finished || board.get_result() == GameResult::Continue {
+            return None;
+        }
+
+        self.peak_density = self.peak_density.max(self.density());
+        self.carried_score += board.get_score();
+        self.total_time += board.get_elapsed_time();
+        if board.get_result() == GameResult::Lost {
+            self.finished = true;
+            return None;
+        }
+        self.boards_cleared += 1;
+        Some(self.board())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn boards_cleared(&self) -> u32 {
+        self.boards_cleared
+    }
+
+    // the session score with the board being played
+    pub fn score(&self, current: &Board) -> u32 {
+        if self.finished {
+            return self.carried_score;
+        }
+        self.carried_score + current.get_score()
+    }
+
+    pub fn summary(&self) -> MarathonSummary {
+        MarathonSummary {
+            boards_cleared: self.boards_cleared,
+            score: self.carried_score,
+            time_ms: self.total_time.as_millis() as u64,
+            peak_density: self.peak_density,
+            finished_at: storage::now_timestamp(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MarathonSummary {
+    pub boards_cleared: u32,
+    pub score: u32,
+    pub time_ms: u64,
+    pub peak_density: f64,
+    // unix seconds
+    pub finished_at: u64,
+}
+
+impl MarathonSummary {
+    pub fn time(&self) -> Duration {
+        Duration::from_millis(self.time_ms)
+    }
+}
+
+// personal bests over every marathon played
+#[derive(Default, Serialize, Deserialize)]
+pub struct MarathonBests {
+    pub sessions: u32,
+    pub most_boards: Option<MarathonSummary>,
+    pub best_score: Option<MarathonSummary>,
+}
+
+impl MarathonBests {
+    // counts the session, and returns whether it set a new best
+    pub fn record(&mut self, summary: &MarathonSummary) -> bool {
+        self.sessions += 1;
+        let mut new_best = false;
+        if self.most_boards.as_ref().is_none_or(|best| summary.boards_cleared > best.boards_cleared) {
+            self.most_boards = Some(summary.clone());
+            new_best = true;
+        }
+        if self.best_score.as_ref().is_none_or(|best| summary.score > best.score) {
+            self.best_score = Some(summary.clone());
+            new_best = true;
+        }
+        new_best
+    }
+
+    pub fn load(path: &Path) -> io::Result<MarathonBests> {
+        storage::load_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+}
+
+pub fn marathon_path() -> PathBuf {
+    storage::data_path(MARATHON_FILE)
+}
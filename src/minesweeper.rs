@@ -1,7 +1,24 @@
 use egui::{Vec2, Color32};
 
-use crate::{texturedb, objs::Board};
+use std::time::Duration;
+use std::io;
+use std::path::PathBuf;
 
+use crate::{api::ApiServer, campaign::{self, Campaign, CampaignProgress}, texturedb, palette, save, stats::{self, GameMode, GameRecord}, highscores::{self, BoardSize, HighScores}, daily::{self, Date, DailyResults}, hotseat::{HotSeat, PlayerRun}, marathon::{self, Marathon, MarathonBests}, flags::{self, FlagsGame}, spectate::{Broadcaster, EventKind, Spectator}, difficulty::Difficulty, editor::{Editor, Verdict}, objs::{self, Board, GameResult}, state::GameState, timeattack::{self, TimeAttack}, view::Cell, endless::{EndlessBoard, DEFAULT_MINES_PER_CHUNK}};
+
+// where the Save button writes to
+const SAVE_FILE: &str = "minesweeper.save";
+
+// where the editor saves to unless told otherwise
+const EDITOR_FILE: &str = "designed.save";
+
+// the new game menu
+const MENU_SIZE: Vec2 = Vec2::new(320.0, 470.0);
+
+// how many tiles of an endless board are on screen in each direction
+const ENDLESS_VIEW: i32 = 15;
+
+#[derive(Clone, Copy)]
 pub struct CustomBoard {
   pub width: usize,
   pub height: usize,
@@ -18,18 +35,6 @@ impl Default for CustomBoard {
   }
 }
 
-fn validate_input(width: usize, height: usize, mines: usize) -> bool {
-  if width < 3 || height < 3 {
-      return false;
-  }
-
-  if mines > (width * height) - 1 {
-      return false;
-  }
-
-  true
-}
-
 fn num_to_word(num: u8) -> String {
   // do it lowercase
   let output = match num {
@@ -48,53 +53,664 @@ fn num_to_word(num: u8) -> String {
 }
 
 fn get_tint(val: String) -> Color32 {
-  // number to color, 0 through 8, make it like a gradient from green to blue
-  match val.as_str() {
-      "zero" => Color32::from_rgb(0, 255, 0),
-      "one" => Color32::from_rgb(0, 186, 45),
-      "two" => Color32::from_rgb(0, 127, 90),
-      "three" => Color32::from_rgb(0, 68, 135),
-      "four" => Color32::from_rgb(0, 9, 180),
-      "five" => Color32::from_rgb(45, 0, 135),
-      "six" => Color32::from_rgb(90, 0, 90),
-      "seven" => Color32::from_rgb(135, 0, 45),
-      "eight" => Color32::from_rgb(180, 0, 0),
+  let (r, g, b) = match val.as_str() {
+      "zero" => palette::number_rgb(0),
+      "one" => palette::number_rgb(1),
+      "two" => palette::number_rgb(2),
+      "three" => palette::number_rgb(3),
+      "four" => palette::number_rgb(4),
+      "five" => palette::number_rgb(5),
+      "six" => palette::number_rgb(6),
+      "seven" => palette::number_rgb(7),
+      "eight" => palette::number_rgb(8),
       // flag and mine
-      "flag" => Color32::from_rgb(255, 255, 0),
-      "mine" => Color32::from_rgb(255, 0, 0),
-      _ => Color32::from_rgb(0, 0, 0),
+      "flag" => palette::FLAG_RGB,
+      "mine" => palette::MINE_RGB,
+      _ => (0, 0, 0),
+  };
+  Color32::from_rgb(r, g, b)
+}
+
+fn star_text(stars: u8) -> String {
+  let stars = stars.min(campaign::MAX_STARS) as usize;
+  "★".repeat(stars) + &"☆".repeat(campaign::MAX_STARS as usize - stars)
+}
+
+fn format_time(time: Option<Duration>) -> String {
+  match time {
+      Some(time) => format!("{:.3}s", time.as_secs_f64()),
+      None => "-".to_string(),
+  }
+}
+
+fn highscore_table(ui: &mut egui::Ui, highscores: &HighScores, size: BoardSize) {
+  let table = highscores.table(size);
+  if table.is_empty() {
+      ui.label("No scores yet.");
+      return;
+  }
+
+  egui::Grid::new(("highscores", size.width, size.height, size.mines)).striped(true).show(ui, |ui| {
+      for (rank, score) in table.iter().enumerate() {
+          ui.label(format!("{}.", rank + 1));
+          ui.label(&score.name);
+          ui.label(format_time(Some(score.time())));
+          ui.end_row();
+      }
+  });
+}
+
+const MONTH_NAMES: [&str; 12] = [
+  "January", "February", "March", "April", "May", "June",
+  "July", "August", "September", "October", "November", "December",
+];
+
+// window size that fits a board of any size
+fn board_window_size(width: usize, height: usize) -> Vec2 {
+  Vec2::new((width * 37) as f32, ((height * 37) + 30) as f32)
+}
+
+// texture name and tint a tile is drawn with
+fn cell_look(cell: Cell) -> (String, Color32) {
+  match cell {
+      // bomb emoji
+      Cell::Mine => ("mine".to_string(), get_tint("mine".to_string())),
+      Cell::Number(adjacent_mines) => (num_to_word(adjacent_mines), get_tint(num_to_word(adjacent_mines))),
+      // flag emoji
+      Cell::Flagged => ("flag".to_string(), get_tint("flag".to_string())),
+      Cell::Hidden => ("base".to_string(), Color32::WHITE),
   }
 }
 
 pub struct Minesweeper {
   board: Board,
-  is_game_over: bool,
-  is_game_won: bool,
-  game_started: bool,
+  state: GameState,
   pub custom_board: CustomBoard,
+  // why the last custom board couldn't be started
+  custom_error: Option<String>,
   window_size: Vec2,
   pub texture_db: texturedb::TextureDatabase,
-  initial_load: bool,
-    pub pure_random: bool,
+  textures_loaded: bool,
+  pub pure_random: bool,
+  endless_board: EndlessBoard,
+  is_endless: bool,
+  // top left tile of the endless viewport
+  view_origin: (i32, i32),
+  save_message: Option<String>,
+  show_stats: bool,
+  stats_records: Vec<GameRecord>,
+  show_highscores: bool,
+  highscores: HighScores,
+  // tab on the high score screen, None shows the custom boards
+  highscore_tab: Option<Difficulty>,
+  confirm_reset: bool,
+  // Some while a new high score is waiting for a name
+  pending_name: Option<String>,
+  // the day of the daily challenge being played
+  daily: Option<Date>,
+  daily_ranked: bool,
+  daily_results: DailyResults,
+  show_daily_history: bool,
+  // year and month shown on the history calendar
+  history_month: (i32, u32),
+  player_names: [String; 2],
+  hot_seat: Option<HotSeat>,
+  show_hot_seat_results: bool,
+  flags_game: Option<FlagsGame>,
+  // streams the classic board to spectators
+  broadcaster: Option<Broadcaster>,
+  // set when this window only watches someone else's game
+  spectator: Option<Spectator>,
+  api: Option<ApiServer>,
+  // the last API board this window has shown or handed over
+  api_version: u64,
+  // the countdown of a time attack game
+  time_attack: Option<TimeAttack>,
+  // time attack settings on the menu
+  time_limit_secs: u64,
+  time_bonus: bool,
+  // lives for new games, 1 is the normal game
+  lives: u8,
+  marathon: Option<Marathon>,
+  marathon_bests: MarathonBests,
+  // set when the marathon that just ended beat a personal best
+  marathon_new_best: bool,
+  // where the tile images and the campaign levels are read from
+  asset_dir: PathBuf,
+  show_campaign: bool,
+  // None when the levels couldn't be read, the error is in campaign_error
+  campaign: Option<Campaign>,
+  campaign_error: Option<String>,
+  campaign_progress: CampaignProgress,
+  // the level being played
+  campaign_level: Option<usize>,
+  // stars of the last run of that level
+  campaign_stars: u8,
+  // Some while the board editor is open
+  editor: Option<Editor>,
+  editor_tool: EditorTool,
+  editor_start: Option<(usize, usize)>,
+  editor_path: String,
+  // the size typed into the editor, applied with Resize
+  editor_size: (usize, usize),
+  editor_message: Option<String>,
 }
 
-impl Minesweeper {
-    pub(crate) fn update_pure_random(&mut self) {
-        self.pure_random = !self.pure_random;
-    }
+// what a click in the editor paints
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditorTool {
+  Mine,
+  Reveal,
+  Start,
 }
 
 impl Minesweeper {
+  pub fn with_assets(asset_dir: &std::path::Path) -> Self {
+      Self {
+          texture_db: texturedb::TextureDatabase::new(asset_dir),
+          asset_dir: asset_dir.to_path_buf(),
+          ..Default::default()
+      }
+  }
+
   pub fn new_board(&mut self, width: usize, height: usize, mines: usize) {
       self.board = Board::new(width, height, mines, self.pure_random);
-      self.is_game_over = false;
-      self.is_game_won = false;
-      self.game_started = true;
+      self.board.set_lives(self.lives);
+      self.state.move_to(GameState::Ready);
+      self.is_endless = false;
+      self.save_message = None;
+      self.pending_name = None;
+      self.daily = None;
+      self.hot_seat = None;
+      self.flags_game = None;
+      self.time_attack = None;
+      self.marathon = None;
+      self.campaign_level = None;
+      self.announce(EventKind::Start);
+  }
+
+  // start a board made elsewhere, e.g. from the command line or a save file
+  pub fn start_game(&mut self, board: Board) {
+      self.update_window_size(board_window_size(board.get_width() as usize, board.get_height() as usize));
+      // straight to the state the board is in, a save can be taken mid game or after it
+      self.state = GameState::of_board(&board);
+      self.pure_random = board.is_pure_random();
+      self.board = board;
+      self.is_endless = false;
+      self.save_message = None;
+      self.pending_name = None;
+      self.daily = None;
+      self.hot_seat = None;
+      self.flags_game = None;
+      self.time_attack = None;
+      self.marathon = None;
+      self.campaign_level = None;
+      self.announce(EventKind::Start);
+  }
+
+  pub fn new_preset_board(&mut self, difficulty: Difficulty) {
+      // increase the window size to fit the board
+      let window_size = match difficulty {
+          Difficulty::Easy => Vec2::new(370.0, 400.0),
+          Difficulty::Medium => Vec2::new(555.0, 550.0),
+          Difficulty::Hard => Vec2::new(925.0, 900.0),
+      };
+      self.update_window_size(window_size);
+      let (width, height, mines) = difficulty.dimensions();
+      self.new_board(width, height, mines);
+  }
+
+  pub fn new_flags_game(&mut self) {
+      let names = self.player_names.clone().map(|name| name.trim().to_string());
+      self.flags_game = Some(FlagsGame::new(names, flags::FLAGS_WIDTH, flags::FLAGS_HEIGHT, flags::FLAGS_MINES, self.pure_random, rand::random()));
+      // room for the score line above the board
+      self.update_window_size(board_window_size(flags::FLAGS_WIDTH, flags::FLAGS_HEIGHT) + Vec2::new(0.0, 40.0));
+      self.is_endless = false;
+      self.state.move_to(GameState::Ready);
+      self.daily = None;
+      self.hot_seat = None;
+      self.time_attack = None;
+      self.marathon = None;
+      self.campaign_level = None;
+  }
+
+  pub fn new_endless_board(&mut self) {
+      self.endless_board = EndlessBoard::new(rand::random(), DEFAULT_MINES_PER_CHUNK);
+      // center the view on the safe start
+      self.view_origin = (-ENDLESS_VIEW / 2, -ENDLESS_VIEW / 2);
+      self.state.move_to(GameState::Ready);
+      self.is_endless = true;
+      self.flags_game = None;
+      self.time_attack = None;
+      self.marathon = None;
+      self.campaign_level = None;
+  }
+
+  pub fn start_time_attack(&mut self, difficulty: Difficulty) {
+      self.new_preset_board(difficulty);
+      let limit = Duration::from_secs(self.time_limit_secs);
+      self.time_attack = Some(if self.time_bonus { TimeAttack::with_bonus(limit) } else { TimeAttack::new(limit) });
+  }
+
+  pub fn open_campaign(&mut self) {
+      match Campaign::load(&self.asset_dir.join(campaign::CAMPAIGN_FILE)) {
+          Ok(loaded) => {
+              self.campaign = Some(loaded);
+              self.campaign_error = None;
+          }
+          Err(err) => {
+              self.campaign = None;
+              self.campaign_error = Some(format!("could not load the campaign: {}", err));
+          }
+      }
+      self.campaign_progress = CampaignProgress::load(&campaign::progress_path()).unwrap_or_else(|err| {
+          eprintln!("could not load campaign progress: {}", err);
+          CampaignProgress::default()
+      });
+      self.show_campaign = true;
+      self.update_window_size(Vec2::new(420.0, 480.0));
+  }
+
+  pub fn close_campaign(&mut self) {
+      self.show_campaign = false;
+      self.update_window_size(MENU_SIZE);
+  }
+
+  pub fn start_campaign_level(&mut self, index: usize) {
+      let level = match self.campaign.as_ref().and_then(|campaign| campaign.levels.get(index)) {
+          Some(level) => level.clone(),
+          None => return,
+      };
+      self.show_campaign = false;
+      self.start_game(level.board());
+      self.time_attack = level.time_limit().map(TimeAttack::new);
+      self.campaign_level = Some(index);
+      self.campaign_stars = 0;
+  }
+
+  // levels count for the campaign only, not the statistics or the high scores
+  fn finish_campaign_level(&mut self, index: usize) {
+      let level = match self.campaign.as_ref().and_then(|campaign| campaign.levels.get(index)) {
+          Some(level) => level,
+          None => return,
+      };
+      self.campaign_stars = self.campaign_progress.record(level, &self.board);
+      if let Err(err) = self.campaign_progress.save(&campaign::progress_path()) {
+          eprintln!("could not save campaign progress: {}", err);
+      }
+  }
+
+  pub fn open_editor(&mut self) {
+      let editor = Editor::new(self.custom_board.width, self.custom_board.height);
+      self.editor_size = (editor.width(), editor.height());
+      self.editor = Some(editor);
+      self.editor_start = None;
+      self.editor_message = None;
+      self.fit_editor_window();
+  }
+
+  pub fn close_editor(&mut self) {
+      self.editor = None;
+      self.update_window_size(MENU_SIZE);
+  }
+
+  fn fit_editor_window(&mut self) {
+      if let Some(editor) = &self.editor {
+          // room for the tools above the grid
+          let size = board_window_size(editor.width(), editor.height()) + Vec2::new(0.0, 120.0);
+          self.update_window_size(Vec2::new(size.x.max(460.0), size.y));
+      }
+  }
+
+  // plays the board being edited, the editor is gone once the game starts
+  pub fn play_edited_board(&mut self) {
+      let editor = match self.editor.take() {
+          Some(editor) => editor,
+          None => return,
+      };
+      if let Err(err) = editor.check() {
+          self.editor_message = Some(err);
+          self.editor = Some(editor);
+          return;
+      }
+      let mut board = editor.board();
+      if let Some((x, y)) = self.editor_start {
+          if !editor.tile(x, y).is_mine {
+              board.reveal(x, y);
+          }
+      }
+      self.start_game(board);
+  }
+
+  pub fn start_marathon(&mut self) {
+      self.play_marathon(Marathon::new(self.pure_random), None);
+  }
+
+  // starts the next board of a marathon, or its first one
+  fn play_marathon(&mut self, marathon: Marathon, board: Option<Board>) {
+      self.start_game(board.unwrap_or_else(|| marathon.board()));
+      // room for the session line above the board
+      self.update_window_size(board_window_size(marathon::MARATHON_WIDTH, marathon::MARATHON_HEIGHT) + Vec2::new(0.0, 20.0));
+      self.marathon = Some(marathon);
+  }
+
+  // a cleared board goes straight on to the next one, a lost one ends the session
+  fn finish_marathon_board(&mut self) {
+      let mut marathon = match self.marathon.take() {
+          Some(marathon) => marathon,
+          None => return,
+      };
+      if let Some(next) = marathon.finish_board(&self.board) {
+          self.play_marathon(marathon, Some(next));
+          return;
+      }
+
+      self.load_marathon_bests();
+      self.marathon_new_best = self.marathon_bests.record(&marathon.summary());
+      if let Err(err) = self.marathon_bests.save(&marathon::marathon_path()) {
+          eprintln!("could not save marathon bests: {}", err);
+      }
+      self.marathon = Some(marathon);
+  }
+
+  fn load_marathon_bests(&mut self) {
+      self.marathon_bests = MarathonBests::load(&marathon::marathon_path()).unwrap_or_else(|err| {
+          eprintln!("could not load marathon bests: {}", err);
+          MarathonBests::default()
+      });
+  }
+
+  // loses the game once the countdown runs out
+  fn check_time_attack(&mut self) {
+      if let (Some(time_attack), GameState::Playing) = (self.time_attack, self.state) {
+          let result = time_attack.check(&mut self.board);
+          self.apply_result(result);
+      }
+  }
+
+  // keeps the game state in step with the board and records finished games
+  fn apply_result(&mut self, result: GameResult) {
+      // moves after the end just repeat the result, it's already been recorded
+      if !self.state.apply(result) || !self.state.is_over() {
+          return;
+      }
+      self.announce(EventKind::Outcome);
+
+      // races are between the two players, they don't count towards anyone's statistics
+      if let Some(hot_seat) = &mut self.hot_seat {
+          hot_seat.record_run(&self.board);
+          return;
+      }
+
+      if let Some(index) = self.campaign_level {
+          self.finish_campaign_level(index);
+          return;
+      }
+
+      // marathons keep their own bests for the whole session
+      if self.marathon.is_some() {
+          self.finish_marathon_board();
+          return;
+      }
+
+      // hand made and loaded boards go into neither the statistics nor the high scores
+      if !self.board.is_ranked() {
+          return;
+      }
+
+      let mode = match (self.daily, self.time_attack) {
+          (Some(_), _) => GameMode::Daily,
+          (None, Some(_)) => GameMode::TimeAttack,
+          (None, None) => GameMode::of_board(&self.board),
+      };
+      if let Err(err) = stats::record_game_as(&self.board, mode) {
+          eprintln!("could not save statistics: {}", err);
+      }
+
+      if let Some(date) = self.daily {
+          // only the first attempt of the day is ranked, practice runs aren't kept
+          if self.daily_ranked {
+              self.load_daily_results();
+              self.daily_results.finish(date, &self.board);
+              self.save_daily_results();
+          }
+          return;
+      }
+
+      // bonus time and extra lives make these times no match for the normal high scores
+      if self.time_attack.is_some() || mode == GameMode::Lives {
+          return;
+      }
+
+      if result == GameResult::Won {
+          self.load_highscores();
+          if self.highscores.qualifies(self.board_size(), self.board.get_elapsed_time()) {
+              self.pending_name = Some(String::new());
+          }
+      }
+  }
+
+  fn board_size(&self) -> BoardSize {
+      BoardSize {
+          width: self.board.get_width(),
+          height: self.board.get_height(),
+          mines: self.board.get_mines(),
+      }
+  }
+
+  fn load_highscores(&mut self) {
+      self.highscores = HighScores::load(&highscores::highscores_path()).unwrap_or_else(|err| {
+          eprintln!("could not load high scores: {}", err);
+          HighScores::default()
+      });
+  }
+
+  fn save_highscores(&self) {
+      if let Err(err) = self.highscores.save(&highscores::highscores_path()) {
+          eprintln!("could not save high scores: {}", err);
+      }
+  }
+
+  pub fn submit_highscore(&mut self) {
+      let name = match self.pending_name.take() {
+          Some(name) if !name.trim().is_empty() => name,
+          Some(_) => "Anonymous".to_string(),
+          None => return,
+      };
+      self.load_highscores();
+      self.highscores.add(self.board_size(), &name, self.board.get_elapsed_time());
+      self.save_highscores();
+  }
+
+  fn load_daily_results(&mut self) {
+      self.daily_results = DailyResults::load(&daily::daily_path()).unwrap_or_else(|err| {
+          eprintln!("could not load daily results: {}", err);
+          DailyResults::default()
+      });
+  }
+
+  fn save_daily_results(&self) {
+      if let Err(err) = self.daily_results.save(&daily::daily_path()) {
+          eprintln!("could not save daily results: {}", err);
+      }
+  }
+
+  pub fn start_daily(&mut self) {
+      let date = Date::today();
+      self.load_daily_results();
+      // the attempt is stored as soon as it starts, so quitting doesn't give a second try
+      let ranked = self.daily_results.start(date);
+      if ranked {
+          self.save_daily_results();
+      }
+
+      self.start_game(daily::daily_board(date));
+      self.daily = Some(date);
+      self.daily_ranked = ranked;
+  }
+
+  pub fn open_daily_history(&mut self) {
+      self.load_daily_results();
+      let (year, month, _) = Date::today().ymd();
+      self.history_month = (year, month);
+      self.show_daily_history = true;
+      self.update_window_size(Vec2::new(420.0, 360.0));
+  }
+
+  pub fn close_daily_history(&mut self) {
+      self.show_daily_history = false;
+      self.update_window_size(MENU_SIZE);
+  }
+
+  pub fn start_hot_seat(&mut self, difficulty: Difficulty) {
+      let (width, height, mines) = difficulty.dimensions();
+      let names = self.player_names.clone().map(|name| name.trim().to_string());
+      self.play_hot_seat(HotSeat::new(names, width, height, mines, self.pure_random, rand::random()));
+  }
+
+  // starts the next turn of a race, or its first one
+  fn play_hot_seat(&mut self, hot_seat: HotSeat) {
+      self.start_game(hot_seat.board());
+      self.hot_seat = Some(hot_seat);
+      self.show_hot_seat_results = false;
+  }
+
+  pub fn next_hot_seat_turn(&mut self) {
+      if let Some(hot_seat) = self.hot_seat.take() {
+          self.play_hot_seat(hot_seat);
+      }
+  }
+
+  pub fn open_hot_seat_results(&mut self) {
+      self.state.move_to(GameState::Menu);
+      self.show_hot_seat_results = true;
+      self.update_window_size(Vec2::new(360.0, 280.0));
+  }
+
+  pub fn close_hot_seat_results(&mut self) {
+      self.hot_seat = None;
+      self.show_hot_seat_results = false;
+      self.prompt_for_new_game();
+  }
+
+  pub fn open_highscores(&mut self) {
+      self.load_highscores();
+      self.show_highscores = true;
+      self.confirm_reset = false;
+      self.update_window_size(Vec2::new(360.0, 440.0));
+  }
+
+  pub fn close_highscores(&mut self) {
+      self.show_highscores = false;
+      self.update_window_size(MENU_SIZE);
+  }
+
+  pub fn open_stats(&mut self) {
+      self.stats_records = stats::load_records(&stats::stats_path()).unwrap_or_else(|err| {
+          eprintln!("could not load statistics: {}", err);
+          Vec::new()
+      });
+      self.load_marathon_bests();
+      self.show_stats = true;
+      self.update_window_size(Vec2::new(620.0, 260.0));
+  }
+
+  pub fn close_stats(&mut self) {
+      self.show_stats = false;
+      self.update_window_size(MENU_SIZE);
+  }
+
+  pub fn start_broadcast(&mut self, address: &str) -> io::Result<()> {
+      self.broadcaster = Some(Broadcaster::bind(address)?);
+      Ok(())
+  }
+
+  // tells spectators and the API about a change to the classic board. Spectators get the
+  // moves from the board's own events, a new board only has to be followed
+  fn announce(&mut self, kind: EventKind) {
+      if let Some(broadcaster) = &mut self.broadcaster {
+          if let EventKind::Start = kind {
+              broadcaster.follow(&mut self.board);
+          }
+          broadcaster.publish(kind, &self.board);
+      }
+      self.share_board();
+  }
+
+  // hands the API the board as it is after a move
+  fn share_board(&mut self) {
+      if let Some(api) = &self.api {
+          self.api_version = api.set_board(self.board.clone());
+      }
+  }
+
+  pub fn start_api(&mut self, address: &str) -> io::Result<()> {
+      self.api = Some(ApiServer::start(address)?);
+      Ok(())
+  }
+
+  // picks up moves made through the API since the last frame
+  fn sync_api_board(&mut self) {
+      let (board, version) = match &self.api {
+          Some(api) if api.version() != self.api_version => match api.board() {
+              Some(board) => board,
+              None => return,
+          },
+          _ => return,
+      };
+      self.api_version = version;
+
+      let same_game = self.state.in_game()
+          && !self.is_endless
+          && self.flags_game.is_none()
+          && board.get_width() == self.board.get_width()
+          && board.get_height() == self.board.get_height()
+          && board.get_seed() == self.board.get_seed();
+      if same_game {
+          let result = board.get_result();
+          self.board = board;
+          if let Some(broadcaster) = &mut self.broadcaster {
+              broadcaster.follow(&mut self.board);
+          }
+          // a game the API finished is recorded like one finished with a click
+          self.apply_result(result);
+      } else {
+          self.start_game(board);
+      }
+  }
+
+  pub fn spectate(&mut self, address: &str, delay: Duration) -> io::Result<()> {
+      self.spectator = Some(Spectator::connect(address, delay)?);
+      self.state.move_to(GameState::Replaying);
+      Ok(())
+  }
+
+  // stops the clock and hides the board, only a click on Resume brings it back
+  pub fn pause(&mut self) {
+      if self.is_endless || self.flags_game.is_some() || self.state != GameState::Playing {
+          return;
+      }
+      self.state.move_to(GameState::Paused);
+      self.board.pause();
+      self.announce(EventKind::Pause);
+  }
+
+  pub fn resume(&mut self) {
+      if self.state == GameState::Paused {
+          self.state.move_to(GameState::Playing);
+          self.board.resume();
+          self.announce(EventKind::Resume);
+      }
+  }
+
+  pub fn pan_view(&mut self, x: i32, y: i32) {
+      self.view_origin.0 += x;
+      self.view_origin.1 += y;
   }
 
   pub fn prompt_for_new_game(&mut self) {
-      self.game_started = false;
-      self.window_size = Vec2::new(300.0, 300.0);
+      self.state.move_to(GameState::Menu);
+      self.window_size = MENU_SIZE;
   }
 
   pub fn update_custom_board(&mut self, width: usize, height: usize, mines: usize) {
@@ -114,17 +730,730 @@ impl Minesweeper {
 
 impl Default for Minesweeper {
   fn default() -> Self {
-      let mut texture = texturedb::TextureDatabase::default();
+      let texture = texturedb::TextureDatabase::default();
       Self {
           board: Board::default(),
-          is_game_over: false,
-          is_game_won: false,
-          game_started: false,
+          state: GameState::Menu,
           custom_board: CustomBoard::default(),
-          window_size: Vec2::new(300.0, 300.0),
+          custom_error: None,
+          window_size: MENU_SIZE,
           texture_db: texture,
-          initial_load: false,
+          textures_loaded: false,
           pure_random: false,
+          endless_board: EndlessBoard::default(),
+          is_endless: false,
+          view_origin: (0, 0),
+          save_message: None,
+          show_stats: false,
+          stats_records: Vec::new(),
+          show_highscores: false,
+          highscores: HighScores::default(),
+          highscore_tab: Some(Difficulty::Easy),
+          confirm_reset: false,
+          pending_name: None,
+          daily: None,
+          daily_ranked: false,
+          daily_results: DailyResults::default(),
+          show_daily_history: false,
+          history_month: (1970, 1),
+          player_names: ["Player 1".to_string(), "Player 2".to_string()],
+          hot_seat: None,
+          show_hot_seat_results: false,
+          flags_game: None,
+          broadcaster: None,
+          spectator: None,
+          api: None,
+          api_version: 0,
+          time_attack: None,
+          time_limit_secs: timeattack::DEFAULT_LIMIT.as_secs(),
+          time_bonus: false,
+          lives: 1,
+          marathon: None,
+          marathon_bests: MarathonBests::default(),
+          marathon_new_best: false,
+          asset_dir: PathBuf::from("assets"),
+          show_campaign: false,
+          campaign: None,
+          campaign_error: None,
+          campaign_progress: CampaignProgress::default(),
+          campaign_level: None,
+          campaign_stars: 0,
+          editor: None,
+          editor_tool: EditorTool::Mine,
+          editor_start: None,
+          editor_path: EDITOR_FILE.to_string(),
+          editor_size: (10, 10),
+          editor_message: None,
+      }
+  }
+}
+
+impl Minesweeper {
+  fn name_entry_ui(&mut self, ui: &mut egui::Ui) {
+      let mut submit = false;
+      ui.horizontal(|ui| {
+          ui.label("New high score! Name:");
+          if let Some(name) = &mut self.pending_name {
+              let response = ui.text_edit_singleline(name);
+              if response.lost_focus() && ui.input().key_pressed(egui::Key::Enter) {
+                  submit = true;
+              }
+          }
+          if ui.button("Save").clicked() {
+              submit = true;
+          }
+      });
+      if submit {
+          self.submit_highscore();
+      }
+  }
+
+  // shown once a marathon is over
+  fn marathon_summary_ui(&self, ui: &mut egui::Ui) {
+      let summary = match &self.marathon {
+          Some(marathon) if marathon.is_finished() => marathon.summary(),
+          _ => return,
+      };
+      ui.label(format!(
+          "Marathon over! Boards cleared: {} Total score: {} Total time: {} Peak density: {:.0}%",
+          summary.boards_cleared,
+          summary.score,
+          format_time(Some(summary.time())),
+          summary.peak_density * 100.0
+      ));
+      if self.marathon_new_best {
+          ui.label("New personal best!");
+      }
+  }
+
+  fn lives_used_ui(&self, ui: &mut egui::Ui) {
+      if self.board.has_lives() {
+          ui.label(format!("Lives used: {}", self.board.get_lives_used()));
+      }
+  }
+
+  fn time_left_ui(&self, ui: &mut egui::Ui) {
+      if let Some(time_attack) = &self.time_attack {
+          let mut text = format!("Time left: {}", format_time(Some(time_attack.remaining(&self.board))));
+          if time_attack.has_bonus() {
+              text += &format!(" (bonus earned: {}s)", time_attack.bonus(&self.board).as_secs());
+          }
+          ui.label(text);
+      }
+  }
+
+  // shown once a campaign level is over
+  fn campaign_controls(&mut self, ui: &mut egui::Ui) {
+      let (index, campaign) = match (self.campaign_level, &self.campaign) {
+          (Some(index), Some(campaign)) => (index, campaign),
+          _ => return,
+      };
+      if self.campaign_stars > 0 {
+          ui.label(format!("Level cleared! {}", star_text(self.campaign_stars)));
+      }
+
+      let next = index + 1;
+      let has_next = next < campaign.levels.len() && self.campaign_progress.is_unlocked(campaign, next);
+      ui.horizontal(|ui| {
+          if ui.button("Retry").clicked() {
+              self.start_campaign_level(index);
+          }
+          if has_next && ui.button("Next Level").clicked() {
+              self.start_campaign_level(next);
+          }
+          if ui.button("Levels").clicked() {
+              self.open_campaign();
+              self.state.move_to(GameState::Menu);
+          }
+      });
+  }
+
+  fn editor_ui(&mut self, ui: &mut egui::Ui) {
+      if !self.textures_loaded {
+          self.textures_loaded = true;
+          self.texture_db.update_all(ui);
+      }
+      ui.heading("Board Editor");
+
+      let mut resize = false;
+      ui.horizontal(|ui| {
+          ui.add(egui::DragValue::new(&mut self.editor_size.0).clamp_range(3..=objs::MAX_SIZE).prefix("Width: "));
+          ui.add(egui::DragValue::new(&mut self.editor_size.1).clamp_range(3..=objs::MAX_SIZE).prefix("Height: "));
+          resize = ui.button("Resize").clicked();
+          ui.separator();
+          ui.selectable_value(&mut self.editor_tool, EditorTool::Mine, "Mines");
+          ui.selectable_value(&mut self.editor_tool, EditorTool::Reveal, "Revealed");
+          ui.selectable_value(&mut self.editor_tool, EditorTool::Start, "Start");
+      });
+
+      let editor = match &mut self.editor {
+          Some(editor) => editor,
+          None => return,
+      };
+      if resize {
+          editor.resize(self.editor_size.0, self.editor_size.1);
+          if self.editor_start.is_some_and(|(x, y)| x >= editor.width() || y >= editor.height()) {
+              self.editor_start = None;
+          }
+      }
+
+      let (mut check, mut save, mut play, mut back) = (false, false, false, false);
+      ui.horizontal(|ui| {
+          ui.label(format!("Mines: {}", editor.mines()));
+          check = ui.button("Check").on_hover_text("Whether the board can be solved from the start without guessing").clicked();
+          ui.add_sized([120.0, 20.0], egui::TextEdit::singleline(&mut self.editor_path));
+          save = ui.button("Save").clicked();
+          play = ui.button("Play").clicked();
+          back = ui.button("Back").clicked();
+      });
+      if let Some(message) = &self.editor_message {
+          ui.label(message);
+      }
+
+      for y in 0..editor.height() {
+          ui.horizontal(|ui| {
+              for x in 0..editor.width() {
+                  let tile = editor.tile(x, y);
+                  let (image, tint) = if tile.is_mine {
+                      cell_look(Cell::Mine)
+                  } else if tile.is_revealed {
+                      cell_look(Cell::Number(tile.adjacent_mines))
+                  } else if self.editor_start == Some((x, y)) {
+                      ("base".to_string(), Color32::LIGHT_GREEN)
+                  } else {
+                      cell_look(Cell::Hidden)
+                  };
+
+                  let button = ui.add(egui::ImageButton::new(
+                      self.texture_db.get_texture(image.as_str()),
+                      Vec2::new(20.0, 20.0))
+                      .tint(tint)
+                  ).on_hover_text(tile.adjacent_mines.to_string());
+
+                  if button.clicked() {
+                      match self.editor_tool {
+                          EditorTool::Mine => editor.toggle_mine(x, y),
+                          EditorTool::Reveal => editor.toggle_revealed(x, y),
+                          EditorTool::Start => {
+                              self.editor_start = if self.editor_start == Some((x, y)) { None } else { Some((x, y)) };
+                          }
+                      }
+                  }
+              }
+          });
+      }
+
+      if check {
+          self.editor_message = Some(match editor.check() {
+              Err(err) => err,
+              Ok(()) => match editor.solve(self.editor_start) {
+                  Verdict::NoGuess => "Solvable without guessing.".to_string(),
+                  Verdict::NeedsGuess { hidden_safe } => format!("Needs a guess, the solver got stuck with {} safe tiles left.", hidden_safe),
+                  Verdict::MineAtStart => "The start tile is a mine.".to_string(),
+              },
+          });
+      }
+      if save {
+          self.editor_message = Some(match editor.save(std::path::Path::new(self.editor_path.trim())) {
+              Ok(()) => format!("Saved to {}", self.editor_path.trim()),
+              Err(err) => err,
+          });
+      }
+      if resize {
+          self.fit_editor_window();
+      }
+      if play {
+          self.play_edited_board();
+      } else if back {
+          self.close_editor();
+      }
+  }
+
+  fn campaign_ui(&mut self, ui: &mut egui::Ui) {
+      ui.heading("Campaign");
+
+      let mut play = None;
+      match &self.campaign {
+          Some(campaign) => {
+              let total = campaign.levels.len() as u32 * campaign::MAX_STARS as u32;
+              ui.label(format!("Stars: {} / {}", self.campaign_progress.total_stars(), total));
+
+              egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                  egui::Grid::new("campaign").striped(true).show(ui, |ui| {
+                      for (index, level) in campaign.levels.iter().enumerate() {
+                          ui.label(format!("{}.", index + 1));
+                          ui.label(&level.name);
+                          let edges = if level.topology == objs::Topology::Wrap { ", no edges" } else { "" };
+                          ui.label(format!("{}x{}, {} mines{}", level.width, level.height, level.mines, edges));
+                          ui.label(star_text(self.campaign_progress.stars(level)));
+                          if self.campaign_progress.is_unlocked(campaign, index) {
+                              if ui.button("Play").clicked() {
+                                  play = Some(index);
+                              }
+                          } else {
+                              ui.label("Locked");
+                          }
+                          ui.end_row();
+                      }
+                  });
+              });
+          }
+          None => {
+              if let Some(err) = &self.campaign_error {
+                  ui.label(err);
+              }
+          }
+      }
+
+      if ui.button("Back").clicked() {
+          self.close_campaign();
+      }
+      if let Some(index) = play {
+          self.start_campaign_level(index);
+      }
+  }
+
+  // nothing of the board shows while it's paused, or while a finished hot seat board waits for
+  // the next player, who would otherwise see where the mines are before starting
+  fn is_board_hidden(&self) -> bool {
+      let handing_over = self.state.is_over()
+          && self.hot_seat.as_ref().is_some_and(|hot_seat| hot_seat.current_player().is_some());
+      self.state == GameState::Paused || handing_over
+  }
+
+  // shown once a player's board is over
+  fn hot_seat_controls(&mut self, ui: &mut egui::Ui) {
+      let next = match &self.hot_seat {
+          Some(hot_seat) => hot_seat.current_player().map(str::to_string),
+          None => return,
+      };
+      match next {
+          Some(name) => {
+              if ui.button(format!("Pass to {}", name)).clicked() {
+                  self.next_hot_seat_turn();
+              }
+          }
+          None => {
+              if ui.button("Show Results").clicked() {
+                  self.open_hot_seat_results();
+              }
+          }
+      }
+  }
+
+  fn hot_seat_results_ui(&mut self, ui: &mut egui::Ui) {
+      ui.heading("Results");
+
+      if let Some(hot_seat) = &self.hot_seat {
+          let names = hot_seat.names();
+          let runs = hot_seat.runs();
+          egui::Grid::new("hot_seat_results").striped(true).show(ui, |ui| {
+              ui.label("");
+              for name in names {
+                  ui.strong(name);
+              }
+              ui.end_row();
+
+              let column = |value: fn(&PlayerRun) -> String| runs.iter().map(value).collect::<Vec<_>>();
+              let rows = [
+                  ("Result", column(|run| if run.result == GameResult::Won { "Won".to_string() } else { "Lost".to_string() })),
+                  ("Time", column(|run| format_time(Some(run.time)))),
+                  ("3BV", column(|run| run.bv3.to_string())),
+                  ("3BV/s", column(|run| format!("{:.2}", run.bv3_per_second()))),
+                  ("Clicks", column(|run| run.clicks.to_string())),
+                  ("Tiles cleared", column(|run| run.cleared.to_string())),
+              ];
+              for (label, values) in rows {
+                  ui.label(label);
+                  for value in values {
+                      ui.label(value);
+                  }
+                  ui.end_row();
+              }
+          });
+
+          match hot_seat.winner() {
+              Some(winner) => ui.heading(format!("{} wins!", names[winner])),
+              None => ui.heading("It's a tie!"),
+          };
+      }
+
+      ui.horizontal(|ui| {
+          if ui.button("Rematch").clicked() {
+              if let Some(hot_seat) = &self.hot_seat {
+                  let rematch = hot_seat.rematch(rand::random());
+                  self.play_hot_seat(rematch);
+              }
+          }
+          if ui.button("Menu").clicked() {
+              self.close_hot_seat_results();
+          }
+      });
+  }
+
+  fn daily_history_ui(&mut self, ui: &mut egui::Ui) {
+      ui.heading("Daily History");
+
+      let (year, month) = self.history_month;
+      ui.horizontal(|ui| {
+          if ui.button("⬅").clicked() {
+              self.history_month = if month == 1 { (year - 1, 12) } else { (year, month - 1) };
+          }
+          ui.label(format!("{} {}", MONTH_NAMES[month as usize - 1], year));
+          if ui.button("➡").clicked() {
+              self.history_month = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+          }
+      });
+
+      let first = Date::from_ymd(year, month, 1);
+      let mut played = 0;
+      let mut won = 0;
+      egui::Grid::new("daily_history").min_col_width(48.0).show(ui, |ui| {
+          for weekday in ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"] {
+              ui.strong(weekday);
+          }
+          ui.end_row();
+
+          // pad the first week so days line up under their weekday
+          for _ in 0..first.weekday() {
+              ui.label("");
+          }
+          for day in 0..daily::days_in_month(year, month) {
+              let date = first.add_days(day as i64);
+              let text = format!("{}", day + 1);
+              match self.daily_results.get(date) {
+                  Some(result) => {
+                      played += 1;
+                      match (result.outcome, result.time_ms) {
+                          (Some(stats::Outcome::Won), Some(time_ms)) => {
+                              won += 1;
+                              ui.colored_label(Color32::GREEN, format!("{} {:.0}s", text, time_ms as f64 / 1000.0))
+                                  .on_hover_text(format!("Won, 3BV {} in {} clicks", result.bv3, result.clicks));
+                          }
+                          (Some(_), _) => {
+                              ui.colored_label(Color32::RED, format!("{} ✖", text)).on_hover_text("Lost");
+                          }
+                          (None, _) => {
+                              ui.colored_label(Color32::GRAY, format!("{} -", text)).on_hover_text("Not finished");
+                          }
+                      }
+                  }
+                  None => {
+                      ui.label(text);
+                  }
+              }
+              if date.weekday() == 6 {
+                  ui.end_row();
+              }
+          }
+      });
+      ui.label(format!("Played {} this month, won {}.", played, won));
+
+      if ui.button("Back").clicked() {
+          self.close_daily_history();
+      }
+  }
+
+  fn highscores_ui(&mut self, ui: &mut egui::Ui) {
+      ui.heading("High Scores");
+
+      ui.horizontal(|ui| {
+          for difficulty in Difficulty::ALL {
+              ui.selectable_value(&mut self.highscore_tab, Some(difficulty), difficulty.name());
+          }
+          ui.selectable_value(&mut self.highscore_tab, None, "Custom");
+      });
+
+      egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+          match self.highscore_tab {
+              Some(difficulty) => highscore_table(ui, &self.highscores, BoardSize::of_difficulty(difficulty)),
+              None => {
+                  let sizes = self.highscores.custom_sizes();
+                  if sizes.is_empty() {
+                      ui.label("No custom games won yet.");
+                  }
+                  for size in sizes {
+                      ui.strong(format!("{}x{}, {} mines", size.width, size.height, size.mines));
+                      highscore_table(ui, &self.highscores, size);
+                  }
+              }
+          }
+      });
+
+      ui.horizontal(|ui| {
+          if ui.button("Back").clicked() {
+              self.close_highscores();
+          }
+
+          if !self.confirm_reset {
+              if ui.button("Reset").clicked() {
+                  self.confirm_reset = true;
+              }
+          } else {
+              ui.label("Delete these scores?");
+              if ui.button("Yes").clicked() {
+                  match self.highscore_tab {
+                      Some(difficulty) => self.highscores.reset(BoardSize::of_difficulty(difficulty)),
+                      None => self.highscores.reset_custom(),
+                  }
+                  self.save_highscores();
+                  self.confirm_reset = false;
+              }
+              if ui.button("No").clicked() {
+                  self.confirm_reset = false;
+              }
+          }
+      });
+  }
+
+  fn stats_ui(&mut self, ui: &mut egui::Ui) {
+      ui.heading("Statistics");
+
+      egui::Grid::new("stats").striped(true).show(ui, |ui| {
+          for header in ["Mode", "Games", "Won", "Win rate", "Streak", "Best streak", "Best time", "Average time"] {
+              ui.strong(header);
+          }
+          ui.end_row();
+
+          for (name, mode) in [("Easy", GameMode::Easy), ("Medium", GameMode::Medium), ("Hard", GameMode::Hard), ("Custom", GameMode::Custom), ("Daily", GameMode::Daily), ("Time Attack", GameMode::TimeAttack), ("Lives", GameMode::Lives)] {
+              let summary = stats::summarize(&self.stats_records, mode);
+              ui.label(name);
+              ui.label(summary.games.to_string());
+              ui.label(summary.wins.to_string());
+              ui.label(format!("{:.0}%", summary.win_rate() * 100.0));
+              ui.label(summary.current_streak.to_string());
+              ui.label(summary.best_streak.to_string());
+              ui.label(format_time(summary.best_time));
+              ui.label(format_time(summary.average_time));
+              ui.end_row();
+          }
+      });
+
+      let bests = &self.marathon_bests;
+      if let (Some(most_boards), Some(best_score)) = (&bests.most_boards, &bests.best_score) {
+          ui.label(format!(
+              "Marathon: {} played, most boards cleared {}, best score {}",
+              bests.sessions, most_boards.boards_cleared, best_score.score
+          ));
+      }
+
+      if ui.button("Back").clicked() {
+          self.close_stats();
+      }
+  }
+
+  fn flags_ui(&mut self, ui: &mut egui::Ui) {
+      let game = match &mut self.flags_game {
+          Some(game) => game,
+          None => return,
+      };
+
+      let names = game.names().clone();
+      let scores = game.scores();
+      ui.horizontal(|ui| {
+          for player in 0..2 {
+              let (r, g, b) = palette::PLAYER_RGB[player];
+              ui.colored_label(Color32::from_rgb(r, g, b), format!("{}: {}", names[player], scores[player]));
+          }
+          ui.label(format!("({} to win)", game.mines_to_win()));
+      });
+
+      let mut new_game = false;
+      if game.is_finished() {
+          match game.winner() {
+              Some(winner) => ui.label(format!("{} wins!", names[winner])),
+              None => ui.label("It's a tie!"),
+          };
+          ui.horizontal(|ui| {
+              if ui.button("Rematch").clicked() {
+                  new_game = true;
+              }
+              if ui.button("New Game").clicked() {
+                  self.state.move_to(GameState::Menu);
+              }
+          });
+      } else {
+          ui.label(format!("{}'s turn, finding a mine gives another go", names[game.current_player()]));
+      }
+
+      for y in 0..game.board().get_height() as usize {
+          ui.horizontal(|ui| {
+              for x in 0..game.board().get_width() as usize {
+                  let (image, tint) = match game.owner(x, y) {
+                      Some(player) => {
+                          let (r, g, b) = palette::PLAYER_RGB[player];
+                          ("mine".to_string(), Color32::from_rgb(r, g, b))
+                      }
+                      None => cell_look(game.board().view().cell(x, y)),
+                  };
+
+                  let button =
+                      ui.add_enabled(!game.is_finished(),
+                          egui::ImageButton::new(
+                              self.texture_db.get_texture(image.as_str()),
+                              Vec2::new(20.0, 20.0))
+                              .tint(tint)
+                          );
+
+                  if button.clicked() {
+                      game.reveal(x, y);
+                      self.state.move_to(GameState::Playing);
+                  }
+              }
+          });
+      }
+
+      if new_game {
+          self.new_flags_game();
+      } else if self.state == GameState::Menu {
+          self.flags_game = None;
+          self.prompt_for_new_game();
+      }
+  }
+
+  fn spectator_ui(&mut self, ui: &mut egui::Ui) {
+      if !self.textures_loaded {
+          self.textures_loaded = true;
+          self.texture_db.update_all(ui);
+      }
+      ui.heading("Minesweeper");
+
+      let spectator = match &mut self.spectator {
+          Some(spectator) => spectator,
+          None => return,
+      };
+
+      let mut watching = format!("Watching {}", spectator.address());
+      if !spectator.delay().is_zero() {
+          watching += &format!(" ({}s behind)", spectator.delay().as_secs());
+      }
+      ui.label(watching);
+
+      let finished = spectator.is_finished();
+      match spectator.poll() {
+          None => {
+              ui.label("Waiting for the game to start...");
+          }
+          Some(event) => {
+              let state = &event.state;
+              let status = match state.status {
+                  GameResult::Continue if matches!(event.kind, EventKind::Pause) => "Paused",
+                  GameResult::Continue => "Playing",
+                  GameResult::Won => "Won!",
+                  GameResult::Lost => "Game Over!",
+              };
+              ui.label(format!(
+                  "{}  Time: {}  Mines remaining: {}",
+                  status,
+                  format_time(Some(Duration::from_millis(event.elapsed_ms))),
+                  state.mines as i32 - state.flags as i32
+              ));
+
+              // leave room for the extra lines above the board
+              self.window_size = board_window_size(state.width as usize, state.height as usize) + Vec2::new(0.0, 40.0);
+
+              // read only, the buttons only keep the layout of the real board
+              for row in &state.board {
+                  ui.horizontal(|ui| {
+                      for symbol in row.chars() {
+                          let (image, tint) = cell_look(Cell::from_symbol(symbol).unwrap_or(Cell::Hidden));
+                          ui.add(egui::ImageButton::new(
+                              self.texture_db.get_texture(image.as_str()),
+                              Vec2::new(20.0, 20.0))
+                              .tint(tint)
+                          );
+                      }
+                  });
+              }
+          }
+      }
+
+      if finished {
+          ui.label("The game has stopped streaming.");
+      }
+      if ui.button("Stop Watching").clicked() {
+          self.spectator = None;
+          self.prompt_for_new_game();
+      }
+  }
+
+  fn endless_ui(&mut self, ui: &mut egui::Ui) {
+      if self.state == GameState::Lost {
+          ui.label(format!(
+              "Game Over! Area cleared: {} Score: {}",
+              self.endless_board.get_cleared(),
+              self.endless_board.score()
+          ));
+      } else {
+          ui.label(format!(
+              "Area cleared: {} Flags placed: {} Chunks explored: {}",
+              self.endless_board.get_cleared(),
+              self.endless_board.get_flags(),
+              self.endless_board.get_chunks_explored()
+          ));
+      }
+      ui.label(format!("Seed: {}", self.endless_board.get_seed()));
+
+      // pan with the arrow keys or the buttons, the buttons move a few tiles at a time
+      ui.horizontal(|ui| {
+          if ui.button("New Game").clicked() {
+              self.prompt_for_new_game();
+          }
+          if ui.button("⬅").clicked() {
+              self.pan_view(-5, 0);
+          }
+          if ui.button("⬆").clicked() {
+              self.pan_view(0, -5);
+          }
+          if ui.button("⬇").clicked() {
+              self.pan_view(0, 5);
+          }
+          if ui.button("➡").clicked() {
+              self.pan_view(5, 0);
+          }
+      });
+      let (left, right, up, down) = {
+          let input = ui.input();
+          (
+              input.key_pressed(egui::Key::ArrowLeft),
+              input.key_pressed(egui::Key::ArrowRight),
+              input.key_pressed(egui::Key::ArrowUp),
+              input.key_pressed(egui::Key::ArrowDown),
+          )
+      };
+      if left { self.pan_view(-1, 0); }
+      if right { self.pan_view(1, 0); }
+      if up { self.pan_view(0, -1); }
+      if down { self.pan_view(0, 1); }
+
+      // only the tiles inside the viewport are drawn (and generated)
+      let (origin_x, origin_y) = self.view_origin;
+      for y in origin_y..origin_y + ENDLESS_VIEW {
+          ui.horizontal(|ui| {
+              for x in origin_x..origin_x + ENDLESS_VIEW {
+                  let (image, tint) = cell_look(self.endless_board.get_cell(x, y));
+                  let button =
+                      ui.add_enabled(self.state != GameState::Lost,
+                          egui::ImageButton::new(
+                              self.texture_db.get_texture(image.as_str()),
+                              Vec2::new(20.0, 20.0))
+                              .tint(tint)
+                          );
+
+                  if button.clicked() {
+                      if self.endless_board.select_tile(x, y) {
+                          self.endless_board.on_lost();
+                          self.state.apply(GameResult::Lost);
+                      } else {
+                          self.state.apply(GameResult::Continue);
+                      }
+                  }
+
+                  if button.secondary_clicked() {
+                      self.endless_board.flag_tile(x, y);
+                  }
+              }
+          });
       }
   }
 }
@@ -132,37 +1461,141 @@ impl Default for Minesweeper {
 impl eframe::App for Minesweeper {
   fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
     frame.set_window_size(self.get_window_size());
-      if self.game_started == true {
+      if self.api.is_some() {
+          // moves can come in through the API at any time
+          ctx.request_repaint_after(Duration::from_millis(100));
+          self.sync_api_board();
+      }
+
+      if self.spectator.is_some() {
+          // events come in from another thread, keep checking for them
+          ctx.request_repaint_after(Duration::from_millis(100));
+          egui::CentralPanel::default().show(ctx, |ui| self.spectator_ui(ui));
+      }
+      else if self.state.in_game() {
+          // P pauses, and so does the window losing focus, which minimizing it does too
+          let pause_pressed = ctx.input().key_pressed(egui::Key::P);
+          if pause_pressed || !ctx.input().raw.has_focus {
+              self.pause();
+          }
+          if self.time_attack.is_some() && self.state == GameState::Playing {
+              // the countdown has to be checked even when nothing is clicked
+              ctx.request_repaint_after(Duration::from_millis(100));
+              self.check_time_attack();
+          }
+          if let Some(broadcaster) = &mut self.broadcaster {
+              if !self.is_endless && self.flags_game.is_none() {
+                  broadcaster.tick(&self.board);
+                  ctx.request_repaint_after(Duration::from_millis(250));
+              }
+          }
           egui::CentralPanel::default().show(ctx, |ui| {
-              if !self.initial_load {
-                  self.initial_load = true;
+              if !self.textures_loaded {
+                  self.textures_loaded = true;
                   self.texture_db.update_all(ui);
               }
               ui.heading("Minesweeper");
 
-              if self.is_game_over {
+              if self.is_endless {
+                  self.endless_ui(ui);
+                  return;
+              }
+
+              if self.flags_game.is_some() {
+                  self.flags_ui(ui);
+                  return;
+              }
+
+              if let Some(player) = self.hot_seat.as_ref().and_then(|hot_seat| hot_seat.current_player()) {
+                  ui.label(format!("{}'s turn", player));
+              }
+
+              if let Some(marathon) = &self.marathon {
+                  if !marathon.is_finished() {
+                      ui.label(format!(
+                          "Marathon board {} ({:.0}% mines) Session score: {}",
+                          marathon.boards_cleared() + 1,
+                          marathon.density() * 100.0,
+                          marathon.score(&self.board)
+                      ));
+                  }
+              }
+
+              if let Some(date) = self.daily {
+                  let kind = if self.daily_ranked { "ranked" } else { "practice, already played today" };
+                  ui.label(format!("Daily challenge {} ({})", date, kind));
+              }
+
+              if self.state == GameState::Lost {
                   ui.label("Game Over!");
-                  ui.label("Score: ".to_string() + &self.board.score.to_string());
+                  ui.label(format!("Score: {} Time: {}", self.board.get_score(), format_time(Some(self.board.get_elapsed_time()))));
+                  self.time_left_ui(ui);
+                  self.lives_used_ui(ui);
+                  self.marathon_summary_ui(ui);
+                  self.campaign_controls(ui);
+                  self.hot_seat_controls(ui);
                   if ui.button("New Game").clicked() {
                       self.prompt_for_new_game();
                   }
-              } else if self.is_game_won {
-                  let elapsed_time = self.board.end_time - self.board.start_time;
-                  ui.label(format!("You won! Score: {}, Time: {}", self.board.score, elapsed_time.as_secs()));
+              } else if self.state == GameState::Won {
+                  let elapsed_time = self.board.get_elapsed_time();
+                  ui.label(format!("You won! Score: {}, Time: {}", self.board.get_score(), format_time(Some(elapsed_time))));
+                  self.time_left_ui(ui);
+                  self.lives_used_ui(ui);
+                  if self.pending_name.is_some() {
+                      self.name_entry_ui(ui);
+                  }
+                  self.campaign_controls(ui);
+                  self.hot_seat_controls(ui);
                   if ui.button("New Game").clicked() {
                       self.prompt_for_new_game();
                   }
               } else {
-                  let info = format!(
+                  let mut info = format!(
                       "Total mines: {} Flags used: {} Mines remaining: {}",
                       self.board.get_mines(),
                       self.board.get_flags(),
-                      self.board.get_mines() - self.board.get_flags()
+                      self.board.view().remaining_mines()
                   );
+                  if self.board.has_lives() {
+                      info += &format!(" Lives: {}", self.board.get_lives());
+                  }
 
                   ui.label(info);
-                  if ui.button("New Game").clicked() {
-                      self.prompt_for_new_game();
+                  match self.time_attack {
+                      Some(time_attack) => ui.label(format!("Time left: {:.1}s Seed: {}", time_attack.remaining(&self.board).as_secs_f64(), self.board.get_seed())),
+                      None => ui.label(format!("Time: {:.1}s Seed: {}", self.board.get_elapsed_time().as_secs_f64(), self.board.get_seed())),
+                  };
+                  if self.state == GameState::Playing {
+                      // keep the clock ticking on screen
+                      ctx.request_repaint_after(Duration::from_millis(100));
+                  }
+                  ui.horizontal(|ui| {
+                      if ui.button("New Game").clicked() {
+                          self.prompt_for_new_game();
+                      }
+                      if self.state == GameState::Paused {
+                          if ui.button("Resume").clicked() {
+                              self.resume();
+                          }
+                          return;
+                      }
+                      if ui.add_enabled(self.state == GameState::Playing, egui::Button::new("Pause"))
+                          .on_hover_text("P")
+                          .clicked()
+                      {
+                          self.pause();
+                      }
+                      if ui.button("Save").clicked() {
+                          let message = match save::save_board(&self.board, std::path::Path::new(SAVE_FILE)) {
+                              Ok(()) => format!("Saved to {}", SAVE_FILE),
+                              Err(err) => err.to_string(),
+                          };
+                          self.save_message = Some(message);
+                      }
+                  });
+                  if let Some(message) = &self.save_message {
+                      ui.label(message);
                   }
               }
               // display the board
@@ -170,49 +1603,57 @@ impl eframe::App for Minesweeper {
                   ui.horizontal(|ui| {
                       for x in 0..self.board.get_width() as usize {
                           //ui.add(egui::ImageButton::new(self.texture_db.get_texture("base"), Vec2::new(16.0, 16.0)));
-                          let tile = self.board.get_tile(x, y);
-                          let mut image = self.texture_db.get_texture("base");
-                          let mut tint = Color32::WHITE;
-                          if tile.is_revealed {
-                              if tile.is_mine {
-                                  // bomb emoji
-                                  image = self.texture_db.get_texture("mine");
-                                  tint = get_tint("mine".to_string());
-                              } else {
-                                  image = self.texture_db.get_texture(num_to_word(tile.adjacent_mines).as_str());
-                                  tint = get_tint(num_to_word(tile.adjacent_mines));
-                              }
-                          } else if tile.is_flagged {
-                              // flag emoji
-                              image = self.texture_db.get_texture("flag");
-                              tint = get_tint("flag".to_string());
-                          }
+                          // a paused board is all hidden, so pausing can't be used to study it
+                          let cell = if self.is_board_hidden() { Cell::Hidden } else { self.board.view().cell(x, y) };
+                          let (image, tint) = cell_look(cell);
 
                           let button =
-                              ui.add_enabled(!self.is_game_over, 
+                              ui.add_enabled(!matches!(self.state, GameState::Lost | GameState::Paused), 
                                   egui::ImageButton::new(
-                                      image, 
+                                      self.texture_db.get_texture(image.as_str()), 
                                       Vec2::new(20.0, 20.0))
                                       .tint(tint)
                                   );
 
                           if button.clicked() {
-                              self.is_game_over = self.board.select_tile(x, y);
-                              self.is_game_won = self.board.is_win();
-
-                              if self.is_game_over {
-                                  self.board.on_lost();
-                              }
+                              let result = self.board.reveal(x, y);
+                              self.share_board();
+                              self.apply_result(result);
                           }
 
                           if button.secondary_clicked() {
-                              self.board.flag_tile(x, y);
+                              self.board.flag(x, y);
+                              self.share_board();
+                          }
+
+                          if button.middle_clicked() {
+                              let result = self.board.chord(x, y);
+                              self.share_board();
+                              self.apply_result(result);
                           }
                       }
                   });
               }
           });
       }
+      else if self.show_stats {
+          egui::CentralPanel::default().show(ctx, |ui| self.stats_ui(ui));
+      }
+      else if self.editor.is_some() {
+          egui::CentralPanel::default().show(ctx, |ui| self.editor_ui(ui));
+      }
+      else if self.show_campaign {
+          egui::CentralPanel::default().show(ctx, |ui| self.campaign_ui(ui));
+      }
+      else if self.show_highscores {
+          egui::CentralPanel::default().show(ctx, |ui| self.highscores_ui(ui));
+      }
+      else if self.show_daily_history {
+          egui::CentralPanel::default().show(ctx, |ui| self.daily_history_ui(ui));
+      }
+      else if self.show_hot_seat_results {
+          egui::CentralPanel::default().show(ctx, |ui| self.hot_seat_results_ui(ui));
+      }
       else {
 
 
@@ -221,8 +1662,8 @@ impl eframe::App for Minesweeper {
           let mut mines = self.custom_board.mines.to_string();
           
           egui::CentralPanel::default().show(ctx, |ui| {
-              if !self.initial_load {
-                  self.initial_load = true;
+              if !self.textures_loaded {
+                  self.textures_loaded = true;
                   self.texture_db.update_all(ui);
               }
 
@@ -233,27 +1674,72 @@ impl eframe::App for Minesweeper {
 
               ui.label("Welcome to Minesweeper!");
               ui.label("Select a difficulty to begin.");
-              ui.checkbox(&mut self.pure_random, "Pure Random");
               ui.horizontal(|ui| {
-                  let b1 = ui.add(egui::Button::new("Easy"));
-                  let b2 = ui.add(egui::Button::new("Medium"));
-                  let b3 = ui.add(egui::Button::new("Hard"));
-
-                  if b1.clicked() {
-                      self.update_window_size(Vec2::new(370.0, 400.0));
-                      self.new_board(10, 10, 5);
+                  ui.checkbox(&mut self.pure_random, "Pure Random");
+                  ui.add(egui::DragValue::new(&mut self.lives).clamp_range(1..=9).prefix("Lives: "))
+                      .on_hover_text("Mines that can be hit before the game is lost");
+              });
+              ui.horizontal(|ui| {
+                  for difficulty in Difficulty::ALL {
+                      if ui.button(difficulty.name()).clicked() {
+                          self.new_preset_board(difficulty);
+                      }
                   }
-
-                  if b2.clicked() {
-                      // increase the window size to fit the board
-                      self.update_window_size(Vec2::new(555.0, 550.0));
-                      self.new_board(15, 15, 30);
+              });
+              ui.horizontal(|ui| {
+                  if ui.button("Endless").clicked() {
+                      // big enough for the viewport and the pan controls
+                      self.update_window_size(Vec2::new(555.0, 640.0));
+                      self.new_endless_board();
                   }
-
-                  if b3.clicked() {
-                      // increase the window size to fit the board
-                      self.update_window_size(Vec2 { x: 925.0, y: 900.0 });
-                      self.new_board(25, 25, 50);
+                  if ui.button("Marathon").on_hover_text("Board after board until the first loss").clicked() {
+                      self.start_marathon();
+                  }
+                  if ui.button("Campaign").clicked() {
+                      self.open_campaign();
+                  }
+                  if ui.button("Editor").on_hover_text("Design a board by hand").clicked() {
+                      self.open_editor();
+                  }
+                  if ui.button("Statistics").clicked() {
+                      self.open_stats();
+                  }
+                  if ui.button("High Scores").clicked() {
+                      self.open_highscores();
+                  }
+              });
+              ui.label("Or clear a board against the clock");
+              ui.horizontal(|ui| {
+                  ui.add(egui::DragValue::new(&mut self.time_limit_secs).clamp_range(10..=999).suffix("s"));
+                  ui.checkbox(&mut self.time_bonus, "Bonus time").on_hover_text("Openings and solved flags add time");
+                  for difficulty in Difficulty::ALL {
+                      if ui.button(difficulty.name()).clicked() {
+                          self.start_time_attack(difficulty);
+                      }
+                  }
+              });
+              ui.label("Or race a friend on the same board");
+              ui.horizontal(|ui| {
+                  ui.add_sized([80.0, 20.0], egui::TextEdit::singleline(&mut self.player_names[0]).hint_text("Player 1"));
+                  ui.label("vs");
+                  ui.add_sized([80.0, 20.0], egui::TextEdit::singleline(&mut self.player_names[1]).hint_text("Player 2"));
+              });
+              ui.horizontal(|ui| {
+                  for difficulty in Difficulty::ALL {
+                      if ui.button(format!("Race {}", difficulty.name())).clicked() {
+                          self.start_hot_seat(difficulty);
+                      }
+                  }
+                  if ui.button("Flags").on_hover_text("Take turns finding mines on one board").clicked() {
+                      self.new_flags_game();
+                  }
+              });
+              ui.horizontal(|ui| {
+                  if ui.button("Daily").clicked() {
+                      self.start_daily();
+                  }
+                  if ui.button("Daily History").clicked() {
+                      self.open_daily_history();
                   }
               });
               ui.label("Or play custom");
@@ -272,14 +1758,20 @@ impl eframe::App for Minesweeper {
                   }
 
                   if ui.button("Start Custom Game").clicked() {
-                      let custom_window_size = Vec2::new((self.custom_board.width * 37) as f32, ((self.custom_board.height * 37) + 30) as f32);
-                      self.update_window_size(custom_window_size);
-                      
-                      if validate_input(self.custom_board.width, self.custom_board.height, self.custom_board.mines) {
-                          self.new_board(self.custom_board.width, self.custom_board.height, self.custom_board.mines);
+                      let CustomBoard { width, height, mines } = self.custom_board;
+                      match Board::check_size(width, height, mines) {
+                          Ok(()) => {
+                              self.custom_error = None;
+                              self.update_window_size(board_window_size(width, height));
+                              self.new_board(width, height, mines);
+                          }
+                          Err(err) => self.custom_error = Some(err),
                       }
                   }
               });
+              if let Some(err) = &self.custom_error {
+                  ui.label(err);
+              }
           });
       }
   }
@@ -0,0 +1,658 @@
+// multiplayer over TCP. The server owns every board and only ever sends out what a player
+// could see on screen, so a client can't cheat by reading mine positions off the wire.
+//
+// protocol version 1, JSON lines like protocol.rs. A client starts with a hello and then
+// joins a room, the first player to join a room picks its mode and board size:
+//
+//   {"cmd": "hello", "version": 1, "name": "alice"}
+//   {"cmd": "join", "room": "lobby", "mode": "versus", "difficulty": "medium"}
+//   {"cmd": "join", "room": "lobby", "mode": "coop", "width": 20, "height": 12, "mines": 40}
+//   {"cmd": "reveal", "x": 3, "y": 4}
+//   {"cmd": "flag", "x": 3, "y": 4}
+//   {"cmd": "chord", "x": 3, "y": 4}
+//   {"cmd": "state"}
+//   {"cmd": "restart"}
+//   {"cmd": "quit"}
+//
+// in a versus room every player gets their own board from the same seed and the first to
+// clear it wins. In a coop room everybody plays the one shared board. `restart` deals a new
+// layout to the whole room, once the round is over: somebody won, or every board is finished.
+//
+// the server answers with messages tagged by `type`:
+//
+//   {"type": "welcome", "version": 1, "player": 3}
+//   {"type": "joined", "room": "lobby", "mode": "versus", "players": ["alice", "bob"]}
+//   {"type": "state", "by": "bob", "status": "continue", "width": 10, ..., "board": [...]}
+//   {"type": "progress", "players": [{"name": "alice", "status": "continue", "score": 31}],
+//    "winner": null}
+//   {"type": "error", "error": "say hello first"}
+//
+// `state` is the visible board exactly as in protocol.rs, `by` is whoever made the move.
+// `progress` goes to the whole room after every move so players can follow each other
+// without seeing each other's boards. The seed never leaves the server.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::objs::{Board, GameResult};
+use crate::protocol::{self, BoardState};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const DEFAULT_PORT: u16 = 7878;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoomMode {
+    // everyone races on their own copy of the same layout
+    #[default]
+    Versus,
+    // everyone clears one shared board together
+    Coop,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum ClientMessage {
+    Hello {
+        version: u32,
+        name: String,
+    },
+    Join {
+        room: String,
+        #[serde(default)]
+        mode: RoomMode,
+        difficulty: Option<String>,
+        width: Option<usize>,
+        height: Option<usize>,
+        mines: Option<usize>,
+        #[serde(default)]
+        pure_random: bool,
+    },
+    Reveal { x: usize, y: usize },
+    Flag { x: usize, y: usize },
+    Chord { x: usize, y: usize },
+    State,
+    Restart,
+    Quit,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerProgress {
+    pub name: String,
+    pub status: GameResult,
+    // safe tiles revealed so far
+    pub score: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ServerMessage {
+    Welcome {
+        version: u32,
+        player: u32,
+    },
+    Joined {
+        room: String,
+        mode: RoomMode,
+        players: Vec<String>,
+    },
+    State {
+        by: String,
+        #[serde(flatten)]
+        state: BoardState,
+    },
+    Progress {
+        players: Vec<PlayerProgress>,
+        winner: Option<String>,
+    },
+    Error {
+        error: String,
+    },
+}
+
+fn error(message: impl Into<String>) -> ServerMessage {
+    ServerMessage::Error { error: message.into() }
+}
+
+struct Player {
+    id: u32,
+    name: String,
+    sender: Sender<ServerMessage>,
+}
+
+enum Boards {
+    Versus(HashMap<u32, Board>),
+    Coop(Board),
+}
+
+struct Room {
+    width: usize,
+    height: usize,
+    mines: usize,
+    pure_random: bool,
+    seed: u64,
+    players: Vec<Player>,
+    boards: Boards,
+    // first player to clear their board in a versus room
+    winner: Option<String>,
+}
+
+impl Room {
+    fn new(mode: RoomMode, width: usize, height: usize, mines: usize, pure_random: bool) -> Room {
+        let seed = rand::random();
+        let boards = match mode {
+            RoomMode::Versus => Boards::Versus(HashMap::new()),
+            RoomMode::Coop => Boards::Coop(Board::with_seed(width, height, mines, pure_random, seed)),
+        };
+        Room {
+            width,
+            height,
+            mines,
+            pure_random,
+            seed,
+            players: Vec::new(),
+            boards,
+            winner: None,
+        }
+    }
+
+    fn mode(&self) -> RoomMode {
+        match self.boards {
+            Boards::Versus(_) => RoomMode::Versus,
+            Boards::Coop(_) => RoomMode::Coop,
+        }
+    }
+
+    fn new_board(&self) -> Board {
+        Board::with_seed(self.width, self.height, self.mines, self.pure_random, self.seed)
+    }
+
+    fn add_player(&mut self, player: Player) {
+        let board = self.new_board();
+        if let Boards::Versus(boards) = &mut self.boards {
+            boards.insert(player.id, board);
+        }
+        self.players.push(player);
+    }
+
+    fn remove_player(&mut self, id: u32) {
+        self.players.retain(|player| player.id != id);
+        if let Boards::Versus(boards) = &mut self.boards {
+            boards.remove(&id);
+        }
+    }
+
+    fn board(&self, id: u32) -> Option<&Board> {
+        match &self.boards {
+            Boards::Versus(boards) => boards.get(&id),
+            Boards::Coop(board) => Some(board),
+        }
+    }
+
+    fn board_mut(&mut self, id: u32) -> Option<&mut Board> {
+        match &mut self.boards {
+            Boards::Versus(boards) => boards.get_mut(&id),
+            Boards::Coop(board) => Some(board),
+        }
+    }
+
+    // a restart before this would wipe boards that are still being played
+    fn is_round_over(&self) -> bool {
+        self.winner.is_some()
+            || match &self.boards {
+                Boards::Versus(boards) => boards.values().all(|board| board.get_result() != GameResult::Continue),
+                Boards::Coop(board) => board.get_result() != GameResult::Continue,
+            }
+    }
+
+    fn restart(&mut self) {
+        self.seed = rand::random();
+        self.winner = None;
+        self.boards = match self.mode() {
+            RoomMode::Versus => Boards::Versus(self.players.iter().map(|player| (player.id, self.new_board())).collect()),
+            RoomMode::Coop => Boards::Coop(self.new_board()),
+        };
+    }
+
+    fn name(&self, id: u32) -> String {
+        self.players
+            .iter()
+            .find(|player| player.id == id)
+            .map(|player| player.name.clone())
+            .unwrap_or_default()
+    }
+
+    fn joined(&self, room: &str) -> ServerMessage {
+        ServerMessage::Joined {
+            room: room.to_string(),
+            mode: self.mode(),
+            players: self.players.iter().map(|player| player.name.clone()).collect(),
+        }
+    }
+
+    fn progress(&self) -> ServerMessage {
+        let players = self
+            .players
+            .iter()
+            .filter_map(|player| {
+                self.board(player.id).map(|board| PlayerProgress {
+                    name: player.name.clone(),
+                    status: board.get_result(),
+                    score: board.get_cleared(),
+                })
+            })
+            .collect();
+        ServerMessage::Progress {
+            players,
+            winner: self.winner.clone(),
+        }
+    }
+
+    fn state_for(&self, id: u32, by: &str) -> Option<ServerMessage> {
+        self.board(id).map(|board| ServerMessage::State {
+            by: by.to_string(),
+            state: BoardState::new(board.view()),
+        })
+    }
+
+    fn send(&self, id: u32, message: ServerMessage) {
+        if let Some(player) = self.players.iter().find(|player| player.id == id) {
+            // a failed send means the player is already disconnecting
+            let _ = player.sender.send(message);
+        }
+    }
+
+    fn broadcast(&self, message: impl Fn() -> ServerMessage) {
+        for player in &self.players {
+            let _ = player.sender.send(message());
+        }
+    }
+
+    // sends everyone the board they are looking at after `by` moved
+    fn broadcast_move(&self, by: u32) {
+        let name = self.name(by);
+        match self.boards {
+            Boards::Versus(_) => {
+                if let Some(state) = self.state_for(by, &name) {
+                    self.send(by, state);
+                }
+            }
+            Boards::Coop(_) => {
+                for player in &self.players {
+                    if let Some(state) = self.state_for(player.id, &name) {
+                        let _ = player.sender.send(state);
+                    }
+                }
+            }
+        }
+        self.broadcast(|| self.progress());
+    }
+
+    fn play(&mut self, id: u32, message: &ClientMessage) -> Result<(), String> {
+        let board = self.board_mut(id).ok_or("you aren't playing in this room")?;
+        let (x, y) = match *message {
+            ClientMessage::Reveal { x, y } | ClientMessage::Flag { x, y } | ClientMessage::Chord { x, y } => (x, y),
+            _ => return Ok(()),
+        };
+        if board.get_result() != GameResult::Continue {
+            return Err("the game is over, send restart to play again".to_string());
+        }
+        if x >= board.get_width() as usize || y >= board.get_height() as usize {
+            return Err(format!("({}, {}) is outside the board", x, y));
+        }
+
+        let result = match message {
+            ClientMessage::Reveal { .. } => board.reveal(x, y),
+            ClientMessage::Flag { .. } => board.flag(x, y),
+            _ => board.chord(x, y),
+        };
+        if result == GameResult::Won && self.mode() == RoomMode::Versus && self.winner.is_none() {
+            self.winner = Some(self.name(id));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Server {
+    rooms: Mutex<HashMap<String, Room>>,
+    next_id: AtomicU32,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server::default()
+    }
+
+    // serves clients until the listener fails, one thread per connection
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = Arc::clone(&self);
+            thread::spawn(move || {
+                // the client went away, nothing more to do for them
+                let _ = server.handle_client(stream);
+            });
+        }
+        Ok(())
+    }
+
+    fn handle_client(&self, stream: TcpStream) -> io::Result<()> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let (sender, receiver) = mpsc::channel::<ServerMessage>();
+
+        let mut writer = stream.try_clone()?;
+        thread::spawn(move || {
+            for message in receiver {
+                let line = serde_json::to_string(&message).unwrap();
+                if writeln!(writer, "{}", line).and_then(|_| writer.flush()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut name = None;
+        let mut room = None;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let message = match serde_json::from_str::<ClientMessage>(&line) {
+                Ok(message) => message,
+                Err(err) => {
+                    let _ = sender.send(error(format!("bad command: {}", err)));
+                    continue;
+                }
+            };
+            if let ClientMessage::Quit = message {
+                break;
+            }
+            if let Err(err) = self.handle(id, &sender, &mut name, &mut room, message) {
+                let _ = sender.send(error(err));
+            }
+        }
+
+        if let Some(room) = room {
+            self.leave(id, &room);
+        }
+        Ok(())
+    }
+
+    fn handle(
+        &self,
+        id: u32,
+        sender: &Sender<ServerMessage>,
+        name: &mut Option<String>,
+        joined: &mut Option<String>,
+        message: ClientMessage,
+    ) -> Result<(), String> {
+        if let ClientMessage::Hello { version, name: player_name } = message {
+            if version != PROTOCOL_VERSION {
+                return Err(format!("protocol version {} isn't supported, this server speaks {}", version, PROTOCOL_VERSION));
+            }
+            if player_name.trim().is_empty() {
+                return Err("pick a name".to_string());
+            }
+            *name = Some(player_name.trim().to_string());
+            let _ = sender.send(ServerMessage::Welcome {
+                version: PROTOCOL_VERSION,
+                player: id,
+            });
+            return Ok(());
+        }
+        let player_name = name.clone().ok_or("say hello first")?;
+
+        if let ClientMessage::Join { room, mode, difficulty, width, height, mines, pure_random } = message {
+            if joined.is_some() {
+                return Err("you are already in a room".to_string());
+            }
+            let (width, height, mines) = protocol::board_size(difficulty, width, height, mines)?;
+
+            let mut rooms = self.rooms.lock().unwrap();
+            let entry = rooms
+                .entry(room.clone())
+                .or_insert_with(|| Room::new(mode, width, height, mines, pure_random));
+            entry.add_player(Player {
+                id,
+                name: player_name,
+                sender: sender.clone(),
+            });
+            entry.broadcast(|| entry.joined(&room));
+            if let Some(state) = entry.state_for(id, "") {
+                entry.send(id, state);
+            }
+            entry.broadcast(|| entry.progress());
+            *joined = Some(room);
+            return Ok(());
+        }
+
+        let room_name = joined.as_ref().ok_or("join a room first")?;
+        let mut rooms = self.rooms.lock().unwrap();
+        let room = rooms.get_mut(room_name).ok_or("the room is gone")?;
+        match message {
+            ClientMessage::State => {
+                if let Some(state) = room.state_for(id, "") {
+                    room.send(id, state);
+                }
+                room.send(id, room.progress());
+            }
+            ClientMessage::Restart => {
+                if !room.is_round_over() {
+                    return Err("the round is still being played, restart once it's over".to_string());
+                }
+                room.restart();
+                let by = room.name(id);
+                for player in &room.players {
+                    if let Some(state) = room.state_for(player.id, &by) {
+                        let _ = player.sender.send(state);
+                    }
+                }
+                room.broadcast(|| room.progress());
+            }
+            _ => {
+                room.play(id, &message)?;
+                room.broadcast_move(id);
+            }
+        }
+        Ok(())
+    }
+
+    fn leave(&self, id: u32, room_name: &str) {
+        let mut rooms = self.rooms.lock().unwrap();
+        if let Some(room) = rooms.get_mut(room_name) {
+            room.remove_player(id);
+            if room.players.is_empty() {
+                rooms.remove(room_name);
+            } else {
+                room.broadcast(|| room.joined(room_name));
+                room.broadcast(|| room.progress());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::view::Cell;
+    use std::io::Lines;
+    use std::time::Duration;
+
+    struct Client {
+        stream: TcpStream,
+        lines: Lines<BufReader<TcpStream>>,
+    }
+
+    impl Client {
+        fn connect(address: std::net::SocketAddr, name: &str) -> Client {
+            let stream = TcpStream::connect(address).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            let lines = BufReader::new(stream.try_clone().unwrap()).lines();
+            let mut client = Client { stream, lines };
+            client.send(&format!(r#"{{"cmd": "hello", "version": 1, "name": "{}"}}"#, name));
+            assert!(matches!(client.recv(), ServerMessage::Welcome { version: PROTOCOL_VERSION, .. }));
+            client
+        }
+
+        fn send(&mut self, line: &str) {
+            writeln!(self.stream, "{}", line).unwrap();
+        }
+
+        // joins a room and returns the fresh board the room sends back
+        fn join(&mut self, line: &str) -> BoardState {
+            self.send(line);
+            let (by, state) = self.state();
+            assert_eq!(by, "");
+            state
+        }
+
+        fn recv(&mut self) -> ServerMessage {
+            serde_json::from_str(&self.lines.next().unwrap().unwrap()).unwrap()
+        }
+
+        // skips messages until one the check takes
+        fn recv_until<T>(&mut self, check: impl Fn(ServerMessage) -> Option<T>) -> T {
+            loop {
+                if let Some(value) = check(self.recv()) {
+                    return value;
+                }
+            }
+        }
+
+        fn state(&mut self) -> (String, BoardState) {
+            self.recv_until(|message| match message {
+                ServerMessage::State { by, state } => Some((by, state)),
+                _ => None,
+            })
+        }
+
+        fn progress(&mut self) -> (Vec<PlayerProgress>, Option<String>) {
+            self.recv_until(|message| match message {
+                ServerMessage::Progress { players, winner } => Some((players, winner)),
+                _ => None,
+            })
+        }
+
+        fn error(&mut self) -> String {
+            self.recv_until(|message| match message {
+                ServerMessage::Error { error } => Some(error),
+                _ => None,
+            })
+        }
+
+        // waits until every player in the room has joined
+        fn joined(&mut self, count: usize) {
+            self.recv_until(|message| match message {
+                ServerMessage::Joined { players, .. } if players.len() == count => Some(()),
+                _ => None,
+            })
+        }
+    }
+
+    fn start_server() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || Arc::new(Server::new()).serve(listener));
+        address
+    }
+
+    #[test]
+    fn versus_players_race_on_their_own_boards() {
+        let address = start_server();
+        let mut alice = Client::connect(address, "alice");
+        let mut bob = Client::connect(address, "bob");
+        // eight mines on nine tiles, every reveal ends the game one way or the other
+        let join = r#"{"cmd": "join", "room": "race", "mode": "versus", "width": 3, "height": 3, "mines": 8}"#;
+        alice.join(join);
+        assert_eq!(bob.join(join).status, GameResult::Continue);
+        alice.joined(2);
+
+        alice.send(r#"{"cmd": "reveal", "x": 0, "y": 0}"#);
+        let (by, state) = alice.state();
+        assert_eq!(by, "alice");
+        assert_ne!(state.status, GameResult::Continue);
+        let alice_won = state.status == GameResult::Won;
+        // bob only follows along and never sees alice's board
+        let (players, _) = bob.recv_until(|message| match message {
+            ServerMessage::State { .. } => panic!("bob was sent alice's board"),
+            ServerMessage::Progress { players, winner } if players[0].status != GameResult::Continue => Some((players, winner)),
+            _ => None,
+        });
+        assert_eq!(players[0].name, "alice");
+        assert_eq!(players[1].status, GameResult::Continue);
+
+        if !alice_won {
+            bob.send(r#"{"cmd": "restart"}"#);
+            assert!(bob.error().contains("still being played"));
+        }
+
+        // the same layout gives bob the same outcome for the same move
+        bob.send(r#"{"cmd": "reveal", "x": 0, "y": 0}"#);
+        let (_, state) = bob.state();
+        assert_eq!(state.status == GameResult::Won, alice_won);
+        let (_, winner) = bob.progress();
+        assert_eq!(winner.as_deref(), if alice_won { Some("alice") } else { None });
+
+        bob.send(r#"{"cmd": "restart"}"#);
+        for client in [&mut alice, &mut bob] {
+            let (by, state) = client.state();
+            assert_eq!(by, "bob");
+            assert_eq!(state.status, GameResult::Continue);
+            assert!(state.board.iter().all(|row| row.chars().all(|cell| cell == Cell::Hidden.symbol())));
+            let (_, winner) = client.progress();
+            assert_eq!(winner, None);
+        }
+    }
+
+    #[test]
+    fn coop_players_share_one_board() {
+        let address = start_server();
+        let mut alice = Client::connect(address, "alice");
+        let mut bob = Client::connect(address, "bob");
+        let join = r#"{"cmd": "join", "room": "team", "mode": "coop", "width": 3, "height": 3, "mines": 8}"#;
+        alice.join(join);
+        assert_eq!(bob.join(join).status, GameResult::Continue);
+        alice.joined(2);
+
+        alice.send(r#"{"cmd": "flag", "x": 2, "y": 2}"#);
+        for client in [&mut alice, &mut bob] {
+            let (by, state) = client.state();
+            assert_eq!(by, "alice");
+            assert_eq!(state.flags, 1);
+            assert_eq!(state.board[2].chars().nth(2), Some(Cell::Flagged.symbol()));
+        }
+
+        bob.send(r#"{"cmd": "restart"}"#);
+        assert!(bob.error().contains("still being played"));
+
+        bob.send(r#"{"cmd": "reveal", "x": 0, "y": 0}"#);
+        let mut statuses = Vec::new();
+        for client in [&mut alice, &mut bob] {
+            let (by, state) = client.state();
+            assert_eq!(by, "bob");
+            assert_ne!(state.status, GameResult::Continue);
+            statuses.push(state.status);
+        }
+        assert_eq!(statuses[0], statuses[1]);
+
+        alice.send(r#"{"cmd": "reveal", "x": 1, "y": 1}"#);
+        assert!(alice.error().contains("game is over"));
+
+        alice.send(r#"{"cmd": "restart"}"#);
+        for client in [&mut alice, &mut bob] {
+            let (by, state) = client.state();
+            assert_eq!(by, "alice");
+            assert_eq!(state.status, GameResult::Continue);
+            assert_eq!(state.flags, 0);
+        }
+    }
+}
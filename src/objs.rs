@@ -1,41 +1,212 @@
-use rand::Rng;
-use std::time::Instant;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
+use crate::chunk::ChunkMap;
+use crate::clock::Clock;
+use crate::events::{BoardEvent, Listeners};
+use crate::view::PlayerView;
+
+#[derive(Clone, Default)]
 pub struct Tile {
-    pub is_mine: bool,
-    pub is_revealed: bool,
-    pub is_flagged: bool,
-    pub adjacent_mines: u8,
+    pub(crate) is_mine: bool,
+    pub(crate) is_revealed: bool,
+    pub(crate) is_flagged: bool,
+    // a mine that was revealed by a click, rather than shown at the end
+    pub(crate) is_exploded: bool,
+    pub(crate) adjacent_mines: u8,
+}
+
+impl Tile {
+    pub fn is_mine(&self) -> bool {
+        self.is_mine
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.is_revealed
+    }
+
+    pub fn is_flagged(&self) -> bool {
+        self.is_flagged
+    }
+
+    pub fn is_exploded(&self) -> bool {
+        self.is_exploded
+    }
+
+    pub fn get_adjacent_mines(&self) -> u8 {
+        self.adjacent_mines
+    }
+
+    // flags or unflags a hidden tile, returns whether it's flagged now or None if it's revealed
+    pub(crate) fn toggle_flag(&mut self) -> Option<bool> {
+        if self.is_revealed {
+            return None;
+        }
+        self.is_flagged = !self.is_flagged;
+        Some(self.is_flagged)
+    }
+
+    // shows the tile if it's a mine once the game is lost, returns the bonus for a flagged mine
+    pub(crate) fn show_if_mine(&mut self) -> u32 {
+        if !self.is_mine {
+            return 0;
+        }
+        self.is_revealed = true;
+        if self.is_flagged {
+            10
+        } else {
+            0
+        }
+    }
+}
+
+// the tiles the reveal rules work on, the fixed board and the endless board are both one
+pub(crate) trait TileGrid {
+    fn tile_at(&mut self, x: i32, y: i32) -> &mut Tile;
+    fn neighbours_of(&self, x: i32, y: i32) -> Vec<(i32, i32)>;
+}
+
+// reveals a safe tile, and when it has no mines around it the whole opening it is part of.
+// Flagged, revealed and mine tiles are left alone. Returns every tile revealed with its number
+pub(crate) fn reveal_area(grid: &mut impl TileGrid, x: i32, y: i32) -> Vec<(i32, i32, u8)> {
+    let mut revealed = Vec::new();
+    // without recursion, openings on an endless board can get big
+    let mut pending = vec![(x, y)];
+    while let Some((x, y)) = pending.pop() {
+        let tile = grid.tile_at(x, y);
+        if tile.is_flagged || tile.is_revealed || tile.is_mine {
+            continue;
+        }
+
+        tile.is_revealed = true;
+        let adjacent_mines = tile.adjacent_mines;
+        revealed.push((x, y, adjacent_mines));
+        if adjacent_mines == 0 {
+            pending.extend(grid.neighbours_of(x, y));
+        }
+    }
+    revealed
 }
 
+/// What a move did to the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameResult {
+    Continue,
+    Won,
+    Lost,
+}
+
+/// How the edges of a board behave.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topology {
+    /// The edges are the end of the board, so edge tiles have fewer neighbours.
+    #[default]
+    Flat,
+    /// Opposite edges touch, every tile has eight neighbours.
+    Wrap,
+}
+
+#[derive(Clone)]
 pub struct Board {
-    tiles: Vec<Vec<Tile>>,
+    tiles: ChunkMap<Tile>,
     width: u8,
     height: u8,
     mines: u8,
     flags: u8,
-    pub score: u8,
-    pub pure_random: bool,
-
-    pub start_time: Instant,
-    pub end_time: Instant,
+    score: u32,
+    clicks: u32,
+    pure_random: bool,
+    seed: u64,
+    topology: Topology,
+    result: GameResult,
+    // safe tiles still to reveal, the game is won when it gets to 0
+    safe_left: u32,
+    // mines that can still be hit, the game is lost when it gets to 0
+    lives: u8,
+    lives_used: u8,
+    listeners: Listeners,
+    clock: Clock,
+    // false for boards put together by hand or loaded from a save, they don't count for records
+    ranked: bool,
 }
 
+
+// the largest board the engine handles on a side
+pub const MAX_SIZE: usize = 100;
+
 impl Board {
+    /// Checks that a board of this size can be played, with a message saying why not.
+    pub fn check_size(width: usize, height: usize, mines: usize) -> Result<(), String> {
+        if !(3..=MAX_SIZE).contains(&width) || !(3..=MAX_SIZE).contains(&height) {
+            return Err(format!("width and height must be between 3 and {}", MAX_SIZE));
+        }
+
+        let max_mines = (width * height - 1).min(u8::MAX as usize);
+        if mines == 0 || mines > max_mines {
+            return Err(format!("mines must be between 1 and {}", max_mines));
+        }
+        Ok(())
+    }
+
     pub fn new(width: usize, height: usize, mines: usize, pure_random: bool) -> Board {
-        let mut tiles = Vec::new();
-        for _ in 0..height {
-            let mut row = Vec::new();
-            for _ in 0..width {
-                row.push(Tile {
-                    is_mine: false,
-                    is_revealed: false,
-                    is_flagged: false,
-                    adjacent_mines: 0,
-                });
+        Self::with_seed(width, height, mines, pure_random, rand::random())
+    }
+
+    /// Same as [`Board::new`], but the mine layout is fully determined by the seed.
+    pub fn with_seed(width: usize, height: usize, mines: usize, pure_random: bool, seed: u64) -> Board {
+        Self::generate(width, height, mines, pure_random, seed, Topology::Flat, None)
+    }
+
+    /// Same as [`Board::check_size`], but also leaves room for the mines outside the 3x3 area
+    /// a safe start keeps clear, wherever the start ends up.
+    pub fn check_safe_start(width: usize, height: usize, mines: usize) -> Result<(), String> {
+        Self::check_size(width, height, mines)?;
+        let max_mines = Self::max_safe_start_mines(width, height);
+        if mines > max_mines {
+            return Err(format!("at most {} mines fit around a safe start", max_mines));
+        }
+        Ok(())
+    }
+
+    /// The most mines a board of this size takes with a safe start.
+    pub fn max_safe_start_mines(width: usize, height: usize) -> usize {
+        (width * height).saturating_sub(9).min(u8::MAX as usize)
+    }
+
+    /// Same as [`Board::with_seed`], but no mines are placed on or around the start tile,
+    /// so revealing it always opens up an area.
+    pub fn with_safe_start(width: usize, height: usize, mines: usize, pure_random: bool, seed: u64, start: (usize, usize)) -> Result<Board, String> {
+        Self::with_topology(width, height, mines, pure_random, seed, Topology::Flat, Some(start))
+    }
+
+    /// A seeded board with the given topology, with or without a safe start.
+    pub fn with_topology(
+        width: usize,
+        height: usize,
+        mines: usize,
+        pure_random: bool,
+        seed: u64,
+        topology: Topology,
+        safe_start: Option<(usize, usize)>,
+    ) -> Result<Board, String> {
+        match safe_start {
+            Some((x, y)) => {
+                Self::check_safe_start(width, height, mines)?;
+                if x >= width || y >= height {
+                    return Err(format!("safe start ({}, {}) is outside the board", x, y));
+                }
             }
-            tiles.push(row);
+            None => Self::check_size(width, height, mines)?,
         }
+        Ok(Self::generate(width, height, mines, pure_random, seed, topology, safe_start))
+    }
+
+    fn generate(width: usize, height: usize, mines: usize, pure_random: bool, seed: u64, topology: Topology, safe_start: Option<(usize, usize)>) -> Board {
+        let tiles = ChunkMap::with_size(width as i32, height as i32);
         let mut board = Board {
             tiles,
             width: width as u8,
@@ -43,19 +214,79 @@ impl Board {
             mines: mines as u8,
             flags: 0,
             score: 0,
-            start_time: Instant::now(),
-            end_time: Instant::now(),
+            clicks: 0,
+            clock: Clock::default(),
             pure_random,
+            seed,
+            topology,
+            result: GameResult::Continue,
+            safe_left: 0,
+            lives: 1,
+            lives_used: 0,
+            listeners: Listeners::default(),
+            ranked: true,
         };
 
-        if !pure_random { board.advanced_place_mines(); } else { board.place_mines(); }
+        // from the size and mines actually stored, so it always matches the tiles
+        board.safe_left = (board.width as u32 * board.height as u32).saturating_sub(board.mines as u32);
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        if !board.pure_random { board.advanced_place_mines(&mut rng, safe_start); } else { board.place_mines(&mut rng, safe_start); }
         board.calculate_adjacent_mines();
 
         board
     }
 
-    fn advanced_place_mines(&mut self) {
-        let mut rng = rand::thread_rng();
+    // rebuilds a board from a saved layout, tiles are in row order
+    pub(crate) fn from_tiles(width: usize, height: usize, tiles: Vec<Tile>, pure_random: bool, seed: u64, score: u32, lives: u8) -> Board {
+        let mut board = Board {
+            tiles: ChunkMap::with_size(width as i32, height as i32),
+            width: width as u8,
+            height: height as u8,
+            mines: tiles.iter().filter(|tile| tile.is_mine).count() as u8,
+            flags: tiles.iter().filter(|tile| tile.is_flagged).count() as u8,
+            score,
+            clicks: 0,
+            clock: Clock::default(),
+            pure_random,
+            seed,
+            topology: Topology::Flat,
+            result: GameResult::Continue,
+            safe_left: 0,
+            lives,
+            lives_used: tiles.iter().filter(|tile| tile.is_exploded).count() as u8,
+            listeners: Listeners::default(),
+            ranked: false,
+        };
+
+        for (i, tile) in tiles.into_iter().enumerate() {
+            *board.tile_mut(i % width, i / width) = tile;
+        }
+        board.calculate_adjacent_mines();
+        board.safe_left = board.tiles_where(|tile| !tile.is_mine && !tile.is_revealed) as u32;
+
+        // a save can be taken after the game is already over
+        if board.lives == 0 || board.tiles_where(|tile| tile.is_mine && tile.is_revealed && !tile.is_exploded) > 0 {
+            board.result = GameResult::Lost;
+        } else if board.safe_left == 0 {
+            board.result = GameResult::Won;
+        }
+        board
+    }
+
+    fn tiles_where(&self, condition: impl Fn(&Tile) -> bool) -> usize {
+        let mut count = 0;
+        for y in 0..self.height as usize {
+            for x in 0..self.width as usize {
+                if condition(self.tile(x, y)) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn advanced_place_mines(&mut self, rng: &mut StdRng, safe_start: Option<(usize, usize)>) {
         let mut mines_placed = 0;
         while mines_placed < self.mines {
             // pick a random tile
@@ -63,31 +294,14 @@ impl Board {
             let y = rng.gen_range(0..self.height);
 
             // if the tile is already a mine, skip it
-            if self.tiles[y as usize][x as usize].is_mine {
+            if self.tile(x as usize, y as usize).is_mine || self.near_start(safe_start, x as usize, y as usize) {
                 continue;
             }
 
-            let mut mines_in_surrounding_tiles: u8 = 0;
-            let mut tiles_in_surrounding_tiles: u8 = 0;
-            for y_offset in -1..=1 {
-                for x_offset in -1..=1 {
-                    // skip the current tile
-                    if x_offset == 0 && y_offset == 0 {
-                        continue;
-                    }
-
-                    // skip tiles that are out of bounds
-                    if x as i8 + x_offset < 0 || x as i8 + x_offset >= self.width as i8 || y as i8 + y_offset < 0 || y as i8 + y_offset >= self.height as i8 {
-                        continue;
-                    }
-
-                    // increment the number of mines and tiles in the surrounding 8 tiles
-                    tiles_in_surrounding_tiles += 1;
-                    if self.tiles[(y as i8 + y_offset) as usize][(x as i8 + x_offset) as usize].is_mine {
-                        mines_in_surrounding_tiles += 1;
-                    }
-                }
-            }
+            // count the mines in the surrounding tiles
+            let neighbours = self.neighbours(x as usize, y as usize);
+            let tiles_in_surrounding_tiles = neighbours.len();
+            let mines_in_surrounding_tiles = neighbours.iter().filter(|&&(x, y)| self.tile(x, y).is_mine).count();
             let likelihood = (mines_in_surrounding_tiles as f32 / tiles_in_surrounding_tiles as f32) * 100.0;
 
             if rng.gen_range(0..100) > likelihood as u8 {
@@ -96,148 +310,369 @@ impl Board {
 
             // place a mine in this tile
             //println!("Placing mine at {}, {} (likelihood: {})", x, y, likelihood);
-            self.tiles[y as usize][x as usize].is_mine = true;
+            self.tile_mut(x as usize, y as usize).is_mine = true;
             mines_placed += 1;
         }
     }
 
-    fn place_mines(&mut self) {
+    fn place_mines(&mut self, rng: &mut StdRng, safe_start: Option<(usize, usize)>) {
         let mut mines_placed = 0;
         while mines_placed < self.mines {
-            let x = rand::random::<usize>() % self.width as usize;
-            let y = rand::random::<usize>() % self.height as usize;
+            let x = rng.gen_range(0..self.width as usize);
+            let y = rng.gen_range(0..self.height as usize);
 
-            if !self.tiles[y][x].is_mine {
-                self.tiles[y][x].is_mine = true;
+            if !self.tile(x, y).is_mine && !self.near_start(safe_start, x, y) {
+                self.tile_mut(x, y).is_mine = true;
                 mines_placed += 1;
             }
         }
     }
 
     fn calculate_adjacent_mines(&mut self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let mut adjacent_mines = 0;
-                for y_offset in -1..=1 {
-                    for x_offset in -1..=1 {
-                        if x_offset == 0 && y_offset == 0 {
-                            continue;
-                        }
+        for y in 0..self.height as usize {
+            for x in 0..self.width as usize {
+                let adjacent_mines = self.neighbours(x, y).into_iter().filter(|&(x, y)| self.tile(x, y).is_mine).count();
+                self.tile_mut(x, y).adjacent_mines = adjacent_mines as u8;
+            }
+        }
+    }
 
-                        let x = x as i8 + x_offset;
-                        let y = y as i8 + y_offset;
+    // whether a tile is on or next to the safe start tile
+    fn near_start(&self, safe_start: Option<(usize, usize)>, x: usize, y: usize) -> bool {
+        match safe_start {
+            Some((start_x, start_y)) => (x, y) == (start_x, start_y) || self.neighbours(start_x, start_y).contains(&(x, y)),
+            None => false,
+        }
+    }
 
-                        if x < 0 || x >= self.width as i8 || y < 0 || y >= self.height as i8 {
-                            continue;
-                        }
 
-                        if self.tiles[y as usize][x as usize].is_mine {
-                            adjacent_mines += 1;
-                        }
-                    }
-                }
-                self.tiles[y as usize][x as usize].adjacent_mines = adjacent_mines;
+    /// Sets how many mines can be hit before the game is lost, 1 is the normal game.
+    /// Only has an effect before the first move.
+    pub fn set_lives(&mut self, lives: u8) {
+        if !self.has_started() {
+            self.lives = lives.max(1);
+        }
+    }
+
+    /// Calls the listener with every [`BoardEvent`] from now on.
+    pub fn subscribe(&mut self, listener: impl FnMut(&BoardEvent) + Send + 'static) {
+        self.listeners.add(Box::new(listener));
+    }
+
+    /// Reveals a tile, opening up its neighbours if it has no adjacent mines.
+    /// Flagged tiles, revealed tiles and coordinates outside the board are ignored.
+    pub fn reveal(&mut self, x: usize, y: usize) -> GameResult {
+        if self.result != GameResult::Continue || !self.in_bounds(x, y) {
+            return self.result;
+        }
+
+        self.clicks += 1;
+        self.clock.start();
+        let hit_mine = self.select_tile(x, y);
+        self.finish_move(hit_mine)
+    }
+
+    /// Toggles the flag on an unrevealed tile.
+    pub fn flag(&mut self, x: usize, y: usize) -> GameResult {
+        if self.result != GameResult::Continue || !self.in_bounds(x, y) {
+            return self.result;
+        }
+
+        self.clicks += 1;
+        self.flag_tile(x, y);
+        self.result
+    }
+
+    /// Reveals every unflagged neighbour of a revealed number once the right
+    /// amount of flags has been placed around it.
+    pub fn chord(&mut self, x: usize, y: usize) -> GameResult {
+        if self.result != GameResult::Continue || !self.in_bounds(x, y) {
+            return self.result;
+        }
+
+        self.clicks += 1;
+        let tile = self.tile(x, y);
+        if !tile.is_revealed || tile.adjacent_mines == 0 {
+            return self.result;
+        }
+
+        // mines that went off count as flags, they're just as known
+        let neighbours = self.neighbours(x, y);
+        let flags = neighbours.iter().filter(|(x, y)| self.tile(*x, *y).is_flagged || self.tile(*x, *y).is_exploded).count();
+        if flags != tile.adjacent_mines as usize {
+            return self.result;
+        }
+
+        let mut hit_mine = false;
+        for (x, y) in neighbours {
+            if self.select_tile(x, y) {
+                hit_mine = true;
             }
         }
+        self.finish_move(hit_mine)
     }
 
+    // reveals a tile for games where mines are claimed instead of lost on, like
+    // Minesweeper Flags. Some(true) for a mine, Some(false) for a safe tile and
+    // None when there was nothing to reveal
+    pub(crate) fn claim_tile(&mut self, x: usize, y: usize) -> Option<bool> {
+        if !self.in_bounds(x, y) {
+            return None;
+        }
 
-    pub fn select_tile(&mut self, x: usize, y: usize) -> bool {
-        let tile = &mut self.tiles[y][x];
-        if tile.is_flagged {
-            return false;
+        let tile = self.tile(x, y);
+        if tile.is_revealed || tile.is_flagged {
+            return None;
         }
 
-        if tile.is_revealed {
-            return false;
+        self.clicks += 1;
+        self.clock.start();
+        if self.tile(x, y).is_mine {
+            self.tile_mut(x, y).is_revealed = true;
+            return Some(true);
         }
+        self.select_tile(x, y);
+        Some(false)
+    }
 
-        tile.is_revealed = true;
-        if tile.is_mine {
-            return true;
+    // reveals a tile for the player before the game starts, like a campaign level's
+    // safe start. It isn't counted as a click and doesn't start the clock
+    pub(crate) fn open_start(&mut self, x: usize, y: usize) -> GameResult {
+        if self.result != GameResult::Continue || !self.in_bounds(x, y) {
+            return self.result;
         }
 
-        if tile.adjacent_mines == 0 {
-            for y_offset in -1..=1 {
-                for x_offset in -1..=1 {
-                    if x_offset == 0 && y_offset == 0 {
-                        continue;
-                    }
+        let hit_mine = self.select_tile(x, y);
+        self.finish_move(hit_mine)
+    }
 
-                    let x = x as i8 + x_offset;
-                    let y = y as i8 + y_offset;
+    fn finish_move(&mut self, hit_mine: bool) -> GameResult {
+        if hit_mine {
+            self.on_lost();
+            self.result = GameResult::Lost;
+        } else if self.safe_left == 0 {
+            self.clock.stop();
+            self.result = GameResult::Won;
+            self.listeners.emit(BoardEvent::Won);
+        }
+        self.result
+    }
 
-                    if x < 0 || x >= self.width as i8 || y < 0 || y >= self.height as i8 {
-                        continue;
-                    }
+    /// Ends the game as lost, for rules outside the board like running out of time.
+    pub fn lose(&mut self) -> GameResult {
+        if self.result == GameResult::Continue {
+            self.on_lost();
+            self.result = GameResult::Lost;
+        }
+        self.result
+    }
 
-                    if self.select_tile(x as usize, y as usize) {
-                        //println!("Mine found at {}, {}", x, y);
-                    } else {
-                        //println!("No mine found at {}, {}", x, y);
-                    }
+    fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width as usize && y < self.height as usize
+    }
+
+    pub(crate) fn neighbours(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let mut neighbours = Vec::new();
+        for y_offset in -1..=1 {
+            for x_offset in -1..=1 {
+                if x_offset == 0 && y_offset == 0 {
+                    continue;
+                }
+
+                let (width, height) = (self.width as i32, self.height as i32);
+                let x = x as i32 + x_offset;
+                let y = y as i32 + y_offset;
+                match self.topology {
+                    Topology::Flat if x < 0 || x >= width || y < 0 || y >= height => continue,
+                    Topology::Flat => neighbours.push((x as usize, y as usize)),
+                    Topology::Wrap => neighbours.push((x.rem_euclid(width) as usize, y.rem_euclid(height) as usize)),
                 }
             }
         }
+        neighbours
+    }
 
-        // increase the score by 1
-        self.score += 1;
+    fn select_tile(&mut self, x: usize, y: usize) -> bool {
+        let tile = self.tile_mut(x, y);
+        if tile.is_flagged || tile.is_revealed {
+            return false;
+        }
+
+        if tile.is_mine {
+            // with lives left the mine stays on the board as exploded and the game goes on
+            tile.is_revealed = true;
+            tile.is_exploded = true;
+            self.lives = self.lives.saturating_sub(1);
+            self.lives_used += 1;
+            self.listeners.emit(BoardEvent::Exploded { x, y });
+            return self.lives == 0;
+        }
+
+        for (x, y, adjacent_mines) in reveal_area(self, x as i32, y as i32) {
+            self.safe_left -= 1;
+            self.score += 1;
+            self.listeners.emit(BoardEvent::TileRevealed { x: x as usize, y: y as usize, adjacent_mines });
+        }
         false
     }
 
-    pub fn flag_tile(&mut self, x: usize, y: usize) {
+    fn flag_tile(&mut self, x: usize, y: usize) {
         if self.flags == self.mines {
             return;
         }
 
-        let tile = &mut self.tiles[y][x];
-        if tile.is_revealed {
-            println!("Tile is already revealed");
-            return;
-        }
-
-        tile.is_flagged = !tile.is_flagged;
-        if tile.is_flagged {
+        let flagged = match self.tile_mut(x, y).toggle_flag() {
+            Some(flagged) => flagged,
+            None => return,
+        };
+        if flagged {
             self.flags += 1;
         } else {
             self.flags -= 1;
         }
+        self.listeners.emit(BoardEvent::Flagged { x, y, flagged });
     }
 
-    pub fn is_win(&mut self) -> bool {
-        self.on_game_end();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let tile = &self.tiles[y as usize][x as usize];
-                if !tile.is_mine && !tile.is_revealed {
-                    return false;
-                }
+    fn on_lost(&mut self) {
+        self.clock.stop();
+        let mut bonus = 0;
+        for y in 0..self.height as usize {
+            for x in 0..self.width as usize {
+                bonus += self.tile_mut(x, y).show_if_mine();
             }
         }
-        true
+        self.score += bonus;
     }
 
-    pub fn on_game_end(&mut self) {
-        if (self.end_time - self.start_time).as_secs() == 0 {
-            self.end_time = Instant::now();
-        }
+    /// Whether anything has been revealed yet.
+    pub fn has_started(&self) -> bool {
+        (self.safe_left as usize) < self.width as usize * self.height as usize - self.mines as usize
+            || self.result != GameResult::Continue
+    }
+
+    pub fn get_result(&self) -> GameResult {
+        self.result
+    }
+
+    pub fn get_score(&self) -> u32 {
+        self.score
+    }
+
+    /// Safe tiles revealed so far. Unlike the score it leaves out the bonus for flagged mines.
+    pub fn get_cleared(&self) -> u32 {
+        (self.width as u32 * self.height as u32).saturating_sub(self.mines as u32) - self.safe_left
     }
 
-    pub fn on_lost(&mut self) {
-        self.on_game_end();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let tile = &mut self.tiles[y as usize][x as usize];
-                if tile.is_mine {
-                    tile.is_revealed = true;
-                    if tile.is_flagged {
-                        self.score += 10;
+    /// Mines that can still be hit before the game is lost.
+    pub fn get_lives(&self) -> u8 {
+        self.lives
+    }
+
+    /// Whether the game is played with more than one life.
+    pub fn has_lives(&self) -> bool {
+        self.lives as u16 + self.lives_used as u16 > 1
+    }
+
+    /// Mines hit so far, including the one that ended the game.
+    pub fn get_lives_used(&self) -> u8 {
+        self.lives_used
+    }
+
+    pub fn get_clicks(&self) -> u32 {
+        self.clicks
+    }
+
+    /// The least number of clicks that clears the board without flagging:
+    /// one per opening plus one per safe tile no opening reaches.
+    pub fn get_3bv(&self) -> u32 {
+        let width = self.width as usize;
+        let mut reached = vec![false; width * self.height as usize];
+        let mut bv = 0;
+
+        for y in 0..self.height as usize {
+            for x in 0..width {
+                let tile = self.tile(x, y);
+                if tile.is_mine || tile.adjacent_mines != 0 || reached[y * width + x] {
+                    continue;
+                }
+
+                // flood the opening, it takes its numbered border with it
+                bv += 1;
+                reached[y * width + x] = true;
+                let mut pending = vec![(x, y)];
+                while let Some((x, y)) = pending.pop() {
+                    for (x, y) in self.neighbours(x, y) {
+                        if reached[y * width + x] {
+                            continue;
+                        }
+                        reached[y * width + x] = true;
+                        if self.tile(x, y).adjacent_mines == 0 {
+                            pending.push((x, y));
+                        }
                     }
                 }
             }
         }
 
-        //self.print_board();
+        for y in 0..self.height as usize {
+            for x in 0..width {
+                if !self.tile(x, y).is_mine && !reached[y * width + x] {
+                    bv += 1;
+                }
+            }
+        }
+        bv
+    }
+
+    pub fn is_pure_random(&self) -> bool {
+        self.pure_random
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_topology(&self) -> Topology {
+        self.topology
+    }
+
+    // for loaded boards, the numbers are worked out again for the new neighbours
+    pub(crate) fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.calculate_adjacent_mines();
+    }
+
+    /// Whether the board was dealt by the game, only those go into statistics and high scores.
+    pub fn is_ranked(&self) -> bool {
+        self.ranked
+    }
+
+    /// Time played so far, from the first reveal to the win or loss, without the time spent paused.
+    pub fn get_elapsed_time(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Stops the clock until [`Board::resume`], only while the game is running.
+    pub fn pause(&mut self) {
+        self.clock.pause();
+    }
+
+    pub fn resume(&mut self) {
+        self.clock.resume();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.clock.is_paused()
+    }
+
+    // forgets the time so far, for boards that are set up with a move already made
+    pub(crate) fn reset_clock(&mut self) {
+        self.clock.reset();
+    }
+
+    // time already played on a loaded board, the clock carries on from it on the next move
+    pub(crate) fn set_elapsed_time(&mut self, elapsed: Duration) {
+        self.clock = Clock::with_elapsed(elapsed);
     }
 
     pub fn get_mines(&self) -> u8 {
@@ -256,8 +691,39 @@ impl Board {
         self.width
     }
 
-    pub fn get_tile(&mut self, x: usize, y: usize) -> &Tile {
-        &self.tiles[y][x]
+    // the whole tile, mine or not, frontends go through view() instead
+    pub(crate) fn get_tile(&self, x: usize, y: usize) -> &Tile {
+        self.tile(x, y)
+    }
+
+    /// What the player can see of the board, without the mines.
+    pub fn view(&self) -> PlayerView<'_> {
+        PlayerView::new(self)
+    }
+
+    // (x, y) has to be on the board. The chunks along the edges go on past it, so without the
+    // check a tile off the board would quietly read as an empty one
+    fn tile(&self, x: usize, y: usize) -> &Tile {
+        assert!(self.in_bounds(x, y), "({}, {}) is outside the {}x{} board", x, y, self.width, self.height);
+        self.tiles.get(x as i32, y as i32).unwrap()
+    }
+
+    fn tile_mut(&mut self, x: usize, y: usize) -> &mut Tile {
+        assert!(self.in_bounds(x, y), "({}, {}) is outside the {}x{} board", x, y, self.width, self.height);
+        self.tiles.get_mut(x as i32, y as i32).unwrap()
+    }
+}
+
+impl TileGrid for Board {
+    fn tile_at(&mut self, x: i32, y: i32) -> &mut Tile {
+        self.tile_mut(x as usize, y as usize)
+    }
+
+    fn neighbours_of(&self, x: i32, y: i32) -> Vec<(i32, i32)> {
+        self.neighbours(x as usize, y as usize)
+            .into_iter()
+            .map(|(x, y)| (x as i32, y as i32))
+            .collect()
     }
 }
 
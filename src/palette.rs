@@ -0,0 +1,23 @@
+// colors shared by every frontend, as rgb triples
+
+// number to color, 0 through 8, like a gradient from green to blue to red
+pub fn number_rgb(num: u8) -> (u8, u8, u8) {
+    match num {
+        0 => (0, 255, 0),
+        1 => (0, 186, 45),
+        2 => (0, 127, 90),
+        3 => (0, 68, 135),
+        4 => (0, 9, 180),
+        5 => (45, 0, 135),
+        6 => (90, 0, 90),
+        7 => (135, 0, 45),
+        8 => (180, 0, 0),
+        _ => (0, 0, 0),
+    }
+}
+
+pub const FLAG_RGB: (u8, u8, u8) = (255, 255, 0);
+pub const MINE_RGB: (u8, u8, u8) = (255, 0, 0);
+
+// claimed mines in two player games, blue for the first player and red for the second
+pub const PLAYER_RGB: [(u8, u8, u8); 2] = [(40, 110, 255), (255, 60, 60)];
@@ -0,0 +1,189 @@
+// JSON lines protocol for driving a game from another program.
+//
+// every request is one JSON object on its own line, every reply is one line too:
+//
+//   {"cmd": "new", "width": 10, "height": 10, "mines": 10, "seed": 42, "pure_random": false}
+//   {"cmd": "new", "difficulty": "medium", "seed": 42}
+//   {"cmd": "reveal", "x": 3, "y": 4}
+//   {"cmd": "flag", "x": 3, "y": 4}
+//   {"cmd": "chord", "x": 3, "y": 4}
+//   {"cmd": "state"}
+//   {"cmd": "quit"}
+//
+// replies are either the visible state of the board
+//
+//   {"status": "continue", "width": 10, "height": 10, "mines": 10, "flags": 1, "score": 12,
+//    "board": ["##1.......", "#F1......."]}
+//
+// or an error, after which the game carries on unchanged
+//
+//   {"error": "(12, 3) is outside the board"}
+//
+// board rows use `#` for hidden tiles, `F` for flags, `0`-`8` for revealed numbers and
+// `*` for mines, which only show up once the game is over. The seed is never echoed back.
+
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::objs::{Board, GameResult};
+use crate::view::PlayerView;
+
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum Command {
+    New {
+        difficulty: Option<String>,
+        width: Option<usize>,
+        height: Option<usize>,
+        mines: Option<usize>,
+        seed: Option<u64>,
+        #[serde(default)]
+        pure_random: bool,
+    },
+    Reveal { x: usize, y: usize },
+    Flag { x: usize, y: usize },
+    Chord { x: usize, y: usize },
+    State,
+    Quit,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BoardState {
+    pub status: GameResult,
+    pub width: u8,
+    pub height: u8,
+    pub mines: u8,
+    pub flags: u8,
+    pub score: u32,
+    pub board: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Response {
+    State(BoardState),
+    Error { error: String },
+}
+
+impl BoardState {
+    pub fn new(view: PlayerView) -> BoardState {
+        let rows = (0..view.height())
+            .map(|y| (0..view.width()).map(|x| view.cell(x, y).symbol()).collect())
+            .collect();
+        BoardState {
+            status: view.result(),
+            width: view.width() as u8,
+            height: view.height() as u8,
+            mines: view.mines() as u8,
+            flags: view.flags() as u8,
+            score: view.score(),
+            board: rows,
+        }
+    }
+}
+
+// a board size given either as a difficulty name or as width, height and mines
+pub fn board_size(
+    difficulty: Option<String>,
+    width: Option<usize>,
+    height: Option<usize>,
+    mines: Option<usize>,
+) -> Result<(usize, usize, usize), String> {
+    let (width, height, mines) = match (difficulty, width, height, mines) {
+        (Some(name), None, None, None) => match Difficulty::from_name(&name) {
+            Some(difficulty) => difficulty.dimensions(),
+            None => return Err(format!("unknown difficulty `{}`", name)),
+        },
+        (None, Some(width), Some(height), Some(mines)) => (width, height, mines),
+        _ => return Err("give either a difficulty or width, height and mines".to_string()),
+    };
+    Board::check_size(width, height, mines)?;
+    Ok((width, height, mines))
+}
+
+fn error(message: impl Into<String>) -> Response {
+    Response::Error { error: message.into() }
+}
+
+#[derive(Default)]
+pub struct Session {
+    board: Option<Board>,
+    finished: bool,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    // set once a quit command came in
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn board(&self) -> Option<&Board> {
+        self.board.as_ref()
+    }
+
+    // for frontends that share the game with the session, like the GUI next to the HTTP API
+    pub fn replace_board(&mut self, board: Board) {
+        self.board = Some(board);
+    }
+
+    pub fn handle_line(&mut self, line: &str) -> String {
+        let response = match serde_json::from_str::<Command>(line) {
+            Ok(command) => self.handle(command),
+            Err(err) => error(format!("bad command: {}", err)),
+        };
+        serde_json::to_string(&response).unwrap()
+    }
+
+    pub fn handle(&mut self, command: Command) -> Response {
+        let (x, y) = match command {
+            Command::New { difficulty, width, height, mines, seed, pure_random } => {
+                let (width, height, mines) = match board_size(difficulty, width, height, mines) {
+                    Ok(size) => size,
+                    Err(err) => return error(err),
+                };
+
+                let seed = seed.unwrap_or_else(rand::random);
+                let board = Board::with_seed(width, height, mines, pure_random, seed);
+                let state = BoardState::new(board.view());
+                self.board = Some(board);
+                return Response::State(state);
+            }
+            Command::Quit => {
+                self.finished = true;
+                return match &self.board {
+                    Some(board) => Response::State(BoardState::new(board.view())),
+                    None => error("no game was started"),
+                };
+            }
+            Command::State => {
+                return match &self.board {
+                    Some(board) => Response::State(BoardState::new(board.view())),
+                    None => error("no game, send a new command first"),
+                };
+            }
+            Command::Reveal { x, y } | Command::Flag { x, y } | Command::Chord { x, y } => (x, y),
+        };
+
+        let board = match &mut self.board {
+            Some(board) => board,
+            None => return error("no game, send a new command first"),
+        };
+        if board.get_result() != GameResult::Continue {
+            return error("the game is over, send a new command to play again");
+        }
+        if x >= board.get_width() as usize || y >= board.get_height() as usize {
+            return error(format!("({}, {}) is outside the board", x, y));
+        }
+
+        match command {
+            Command::Reveal { .. } => board.reveal(x, y),
+            Command::Flag { .. } => board.flag(x, y),
+            _ => board.chord(x, y),
+        };
+        Response::State(BoardState::new(board.view()))
+    }
+}
@@ -0,0 +1,260 @@
+// plain text save files, small enough to paste into a bug report
+//
+//   minesweeper-save 1
+//   seed 1234
+//   placement clustered
+//   score 3
+//   lives 2
+//   time 5230
+//   topology wrap
+//   board 4 3
+//   ..*.
+//   oofF
+//   oooo
+//
+// one character per tile:
+//   .  hidden      *  hidden mine
+//   f  flagged     F  flagged mine
+//   o  revealed    X  revealed mine
+//   E  exploded mine, hit while playing with lives
+//
+// the lives line holds the lives left and is left out when that's 1, like in a normal game.
+// The time line holds the milliseconds played so far and is left out before the first move,
+// the topology line is left out for a flat board
+
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::objs::{Board, Tile, Topology};
+
+const HEADER: &str = "minesweeper-save 1";
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "could not access save file: {}", err),
+            SaveError::Format(reason) => write!(f, "invalid save file: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(err: std::io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+fn tile_char(tile: &Tile) -> char {
+    match (tile.is_mine(), tile.is_revealed(), tile.is_flagged()) {
+        (false, true, _) => 'o',
+        (true, true, _) if tile.is_exploded() => 'E',
+        (true, true, _) => 'X',
+        (false, false, true) => 'f',
+        (true, false, true) => 'F',
+        (false, false, false) => '.',
+        (true, false, false) => '*',
+    }
+}
+
+fn char_tile(c: char) -> Option<Tile> {
+    let is_exploded = c == 'E';
+    let (is_mine, is_revealed, is_flagged) = match c {
+        '.' => (false, false, false),
+        '*' => (true, false, false),
+        'f' => (false, false, true),
+        'F' => (true, false, true),
+        'o' => (false, true, false),
+        'X' | 'E' => (true, true, false),
+        _ => return None,
+    };
+    Some(Tile {
+        is_mine,
+        is_revealed,
+        is_flagged,
+        is_exploded,
+        adjacent_mines: 0,
+    })
+}
+
+pub fn board_to_string(board: &Board) -> String {
+    let placement = if board.is_pure_random() { "pure-random" } else { "clustered" };
+    let mut out = format!("{}\nseed {}\nplacement {}\nscore {}\n", HEADER, board.get_seed(), placement, board.get_score());
+    if board.get_lives() != 1 {
+        out += &format!("lives {}\n", board.get_lives());
+    }
+    let time_ms = board.get_elapsed_time().as_millis();
+    if time_ms > 0 {
+        out += &format!("time {}\n", time_ms);
+    }
+    if board.get_topology() == Topology::Wrap {
+        out += "topology wrap\n";
+    }
+    out += &format!("board {} {}\n", board.get_width(), board.get_height());
+    for y in 0..board.get_height() as usize {
+        for x in 0..board.get_width() as usize {
+            out.push(tile_char(board.get_tile(x, y)));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// reads the value of a `key value` line
+fn field<'a>(line: Option<&'a str>, key: &str) -> Result<&'a str, SaveError> {
+    line.and_then(|line| line.strip_prefix(key))
+        .and_then(|rest| rest.strip_prefix(' '))
+        .ok_or_else(|| SaveError::Format(format!("expected a `{}` line", key)))
+}
+
+fn number<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, SaveError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| SaveError::Format(format!("bad {}: {}", what, value)))
+}
+
+pub fn board_from_str(save: &str) -> Result<Board, SaveError> {
+    let mut lines = save.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return Err(SaveError::Format("not a minesweeper save".to_string()));
+    }
+
+    let seed = number(field(lines.next(), "seed")?, "seed")?;
+    let pure_random = match field(lines.next(), "placement")?.trim() {
+        "pure-random" => true,
+        "clustered" => false,
+        other => return Err(SaveError::Format(format!("unknown placement: {}", other))),
+    };
+    let score = number(field(lines.next(), "score")?, "score")?;
+
+    let mut line = lines.next();
+    let lives = match line.and_then(|line| line.strip_prefix("lives ")) {
+        Some(lives) => {
+            line = lines.next();
+            number(lives, "lives")?
+        }
+        None => 1,
+    };
+    let time_ms = match line.and_then(|line| line.strip_prefix("time ")) {
+        Some(time_ms) => {
+            line = lines.next();
+            number(time_ms, "time")?
+        }
+        None => 0,
+    };
+    let topology = match line.and_then(|line| line.strip_prefix("topology ")) {
+        Some(topology) => {
+            line = lines.next();
+            match topology.trim() {
+                "flat" => Topology::Flat,
+                "wrap" => Topology::Wrap,
+                other => return Err(SaveError::Format(format!("unknown topology: {}", other))),
+            }
+        }
+        None => Topology::Flat,
+    };
+
+    let size = field(line, "board")?;
+    let (width, height) = size
+        .split_once(' ')
+        .ok_or_else(|| SaveError::Format(format!("bad board size: {}", size)))?;
+    let width: usize = number(width, "width")?;
+    let height: usize = number(height, "height")?;
+
+    let mut tiles = Vec::new();
+    for y in 0..height {
+        let row = lines
+            .next()
+            .ok_or_else(|| SaveError::Format(format!("missing row {}", y)))?
+            .trim_end();
+        if row.chars().count() != width {
+            return Err(SaveError::Format(format!("row {} is not {} tiles wide", y, width)));
+        }
+        for c in row.chars() {
+            let tile = char_tile(c).ok_or_else(|| SaveError::Format(format!("unknown tile `{}` in row {}", c, y)))?;
+            tiles.push(tile);
+        }
+    }
+
+    // the same limits as a new board, which also keeps a wrapped tile from being its own neighbour
+    let mines = tiles.iter().filter(|tile| tile.is_mine()).count();
+    Board::check_size(width, height, mines).map_err(SaveError::Format)?;
+    let mut board = Board::from_tiles(width, height, tiles, pure_random, seed, score, lives);
+    board.set_elapsed_time(Duration::from_millis(time_ms));
+    board.set_topology(topology);
+    Ok(board)
+}
+
+pub fn save_board(board: &Board, path: &Path) -> Result<(), SaveError> {
+    std::fs::write(path, board_to_string(board))?;
+    Ok(())
+}
+
+pub fn load_board(path: &Path) -> Result<Board, SaveError> {
+    board_from_str(&std::fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objs::GameResult;
+
+    const SAVE: &str = "minesweeper-save 1\nseed 1234\nplacement clustered\nscore 3\nlives 2\nboard 4 3\n..*.\noofF\noooo\n";
+
+    #[test]
+    fn a_save_reads_back_the_same() {
+        let board = board_from_str(SAVE).unwrap();
+        assert_eq!(board.get_seed(), 1234);
+        assert!(!board.is_pure_random());
+        assert_eq!(board.get_lives(), 2);
+        assert_eq!(board.get_mines(), 2);
+        assert_eq!(board.get_result(), GameResult::Continue);
+        assert_eq!(board_to_string(&board), SAVE);
+    }
+
+    #[test]
+    fn a_played_board_reads_back_the_same() {
+        let mut board = Board::with_seed(8, 8, 10, true, 99);
+        let (x, y) = (0..64)
+            .map(|index| (index % 8, index / 8))
+            .find(|&(x, y)| !board.get_tile(x, y).is_mine())
+            .unwrap();
+        board.reveal(x, y);
+        let saved = board_to_string(&board);
+        let loaded = board_from_str(&saved).unwrap();
+        assert_eq!(board_to_string(&loaded), saved);
+        assert_eq!(loaded.get_result(), board.get_result());
+        assert!(!loaded.is_ranked());
+    }
+
+    #[test]
+    fn a_wrapped_board_keeps_its_topology() {
+        let save = "minesweeper-save 1\nseed 0\nplacement pure-random\nscore 0\ntopology wrap\nboard 4 3\n...*\n....\n....\n";
+        let board = board_from_str(save).unwrap();
+        assert_eq!(board.get_topology(), Topology::Wrap);
+        // both left corners see the mine on the far side of the board
+        assert_eq!(board.get_tile(0, 0).get_adjacent_mines(), 1);
+        assert_eq!(board.get_tile(0, 2).get_adjacent_mines(), 1);
+        assert_eq!(board.get_tile(1, 1).get_adjacent_mines(), 0);
+        assert_eq!(board_to_string(&board), save);
+        assert!(board_from_str(&save.replace("board 4 3\n...*\n....\n....", "board 4 2\n...*\n....")).is_err());
+    }
+
+    #[test]
+    fn broken_saves_are_rejected() {
+        assert!(board_from_str("not a save").is_err());
+        assert!(board_from_str(&SAVE.replace("oofF", "oof")).is_err());
+        assert!(board_from_str(&SAVE.replace("oofF", "oofZ")).is_err());
+        assert!(board_from_str(&SAVE.replace("board 4 3", "board 4 2").replace("\noooo", "")).is_err());
+        assert!(board_from_str(&SAVE.replace("..*.", "....").replace("oofF", "oofo")).is_err());
+    }
+}
@@ -0,0 +1,402 @@
+// works out what the visible board says about the tiles that are still hidden.
+//
+// every revealed number is a constraint on the hidden tiles around it. The hidden tiles
+// next to numbers (the frontier) are split into independent groups, every group is searched
+// for all mine layouts that fit its numbers, and the groups are weighed against each other
+// and against the tiles away from the numbers using the total mine count. Flags are the
+// player's guesses and are treated like any other hidden tile.
+
+use crate::view::{Cell, PlayerView};
+
+// search steps a single group may take before it gets estimated instead
+const SEARCH_BUDGET: usize = 200_000;
+
+pub struct Analysis {
+    width: usize,
+    // chance of a mine for every tile, row by row, 0 for revealed tiles
+    probabilities: Vec<f64>,
+    hidden: Vec<bool>,
+    // false when a group was too big to search fully and got estimated
+    exact: bool,
+}
+
+impl Analysis {
+    pub fn probability(&self, x: usize, y: usize) -> f64 {
+        self.probabilities[y * self.width + x]
+    }
+
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+
+    fn hidden_where(&self, keep: impl Fn(f64) -> bool) -> Vec<(usize, usize)> {
+        (0..self.probabilities.len())
+            .filter(|&index| self.hidden[index] && keep(self.probabilities[index]))
+            .map(|index| (index % self.width, index / self.width))
+            .collect()
+    }
+
+    // hidden tiles that can't be mines
+    pub fn safe_tiles(&self) -> Vec<(usize, usize)> {
+        self.hidden_where(|probability| probability == 0.0)
+    }
+
+    // hidden tiles that have to be mines
+    pub fn mines(&self) -> Vec<(usize, usize)> {
+        self.hidden_where(|probability| probability == 1.0)
+    }
+
+    // the hidden tile least likely to be a mine
+    pub fn best_guess(&self) -> Option<(usize, usize)> {
+        (0..self.probabilities.len())
+            .filter(|&index| self.hidden[index])
+            .min_by(|&a, &b| self.probabilities[a].total_cmp(&self.probabilities[b]))
+            .map(|index| (index % self.width, index / self.width))
+    }
+}
+
+struct Constraint {
+    // indices into the group's variables
+    vars: Vec<usize>,
+    mines: usize,
+}
+
+// one independent group of frontier tiles
+struct Group {
+    // tile index of every variable
+    tiles: Vec<usize>,
+    constraints: Vec<Constraint>,
+    // layouts[k] is how many layouts put k mines in the group
+    layouts: Vec<f64>,
+    // mines_at[v][k] is how many of those layouts put a mine on variable v
+    mines_at: Vec<Vec<f64>>,
+    exact: bool,
+}
+
+struct Search<'a> {
+    constraints: &'a [Constraint],
+    var_constraints: Vec<Vec<usize>>,
+    assigned: Vec<bool>,
+    placed: Vec<usize>,
+    open: Vec<usize>,
+    steps: usize,
+    layouts: Vec<f64>,
+    mines_at: Vec<Vec<f64>>,
+}
+
+impl Search<'_> {
+    fn fits(&self, constraint: usize) -> bool {
+        let wanted = self.constraints[constraint].mines;
+        self.placed[constraint] <= wanted && self.placed[constraint] + self.open[constraint] >= wanted
+    }
+
+    // false once the budget ran out
+    fn run(&mut self, var: usize, mines: usize) -> bool {
+        self.steps += 1;
+        if self.steps > SEARCH_BUDGET {
+            return false;
+        }
+        if var == self.assigned.len() {
+            self.layouts[mines] += 1.0;
+            for (v, &mine) in self.assigned.iter().enumerate() {
+                if mine {
+                    self.mines_at[v][mines] += 1.0;
+                }
+            }
+            return true;
+        }
+
+        for mine in [false, true] {
+            self.assigned[var] = mine;
+            for &constraint in &self.var_constraints[var] {
+                self.open[constraint] -= 1;
+                if mine {
+                    self.placed[constraint] += 1;
+                }
+            }
+
+            let fits = self.var_constraints[var].iter().all(|&constraint| self.fits(constraint));
+            let finished = !fits || self.run(var + 1, mines + mine as usize);
+
+            for &constraint in &self.var_constraints[var] {
+                self.open[constraint] += 1;
+                if mine {
+                    self.placed[constraint] -= 1;
+                }
+            }
+            if !finished {
+                return false;
+            }
+        }
+        self.assigned[var] = false;
+        true
+    }
+}
+
+impl Group {
+    fn solve(&mut self) {
+        let count = self.tiles.len();
+        let mut var_constraints = vec![Vec::new(); count];
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            for &var in &constraint.vars {
+                var_constraints[var].push(index);
+            }
+        }
+
+        let mut search = Search {
+            constraints: &self.constraints,
+            var_constraints,
+            assigned: vec![false; count],
+            placed: vec![0; self.constraints.len()],
+            open: self.constraints.iter().map(|constraint| constraint.vars.len()).collect(),
+            steps: 0,
+            layouts: vec![0.0; count + 1],
+            mines_at: vec![vec![0.0; count + 1]; count],
+        };
+        if search.run(0, 0) {
+            let (layouts, mines_at) = (search.layouts, search.mines_at);
+            self.layouts = layouts;
+            self.mines_at = mines_at;
+        } else {
+            self.estimate();
+        }
+    }
+
+    // too many layouts to count, give every tile the densest of its numbers and pretend
+    // the group always holds the expected number of mines
+    fn estimate(&mut self) {
+        let count = self.tiles.len();
+        let mut chances = vec![0.0f64; count];
+        for constraint in &self.constraints {
+            let chance = constraint.mines as f64 / constraint.vars.len() as f64;
+            for &var in &constraint.vars {
+                chances[var] = chances[var].max(chance);
+            }
+        }
+
+        let expected = (chances.iter().sum::<f64>().round() as usize).min(count);
+        self.layouts = vec![0.0; count + 1];
+        self.layouts[expected] = 1.0;
+        self.mines_at = chances
+            .iter()
+            .map(|&chance| {
+                let mut mines_at = vec![0.0; count + 1];
+                mines_at[expected] = chance;
+                mines_at
+            })
+            .collect();
+        self.exact = false;
+    }
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        if x == 0.0 {
+            continue;
+        }
+        for (j, &y) in b.iter().enumerate() {
+            out[i + j] += x * y;
+        }
+    }
+    out
+}
+
+// ln of n choose k, from a table of ln factorials
+fn ln_choose(ln_factorials: &[f64], n: usize, k: usize) -> f64 {
+    ln_factorials[n] - ln_factorials[k] - ln_factorials[n - k]
+}
+
+pub fn analyze(view: PlayerView) -> Analysis {
+    let (width, height) = (view.width(), view.height());
+    let cells: Vec<Cell> = (0..width * height).map(|index| view.cell(index % width, index / width)).collect();
+    let hidden: Vec<bool> = cells.iter().map(|cell| matches!(cell, Cell::Hidden | Cell::Flagged)).collect();
+    let known_mines = cells.iter().filter(|&&cell| cell == Cell::Mine).count();
+
+    // every number that still touches a hidden tile, as tile indices
+    let mut numbers: Vec<(Vec<usize>, usize)> = Vec::new();
+    for (index, cell) in cells.iter().enumerate() {
+        if let Cell::Number(adjacent_mines) = cell {
+            let neighbours = view.neighbours(index % width, index / width);
+            let tiles: Vec<usize> = neighbours
+                .iter()
+                .map(|&(x, y)| y * width + x)
+                .filter(|&tile| hidden[tile])
+                .collect();
+            let found = neighbours.iter().filter(|&&(x, y)| cells[y * width + x] == Cell::Mine).count();
+            if !tiles.is_empty() {
+                numbers.push((tiles, (*adjacent_mines as usize).saturating_sub(found)));
+            }
+        }
+    }
+
+    // split the frontier into groups of tiles linked by shared numbers
+    let mut group_of = vec![usize::MAX; width * height];
+    let mut groups: Vec<Group> = Vec::new();
+    let mut number_done = vec![false; numbers.len()];
+    let mut numbers_at: Vec<Vec<usize>> = vec![Vec::new(); width * height];
+    for (number, (tiles, _)) in numbers.iter().enumerate() {
+        for &tile in tiles {
+            numbers_at[tile].push(number);
+        }
+    }
+    for start in 0..numbers.len() {
+        if number_done[start] {
+            continue;
+        }
+        let mut group = Group {
+            tiles: Vec::new(),
+            constraints: Vec::new(),
+            layouts: Vec::new(),
+            mines_at: Vec::new(),
+            exact: true,
+        };
+        let mut var_of = std::collections::HashMap::new();
+        let mut queue = vec![start];
+        number_done[start] = true;
+        while let Some(number) = queue.pop() {
+            let (tiles, mines) = &numbers[number];
+            let mut vars = Vec::new();
+            for &tile in tiles {
+                let var = *var_of.entry(tile).or_insert_with(|| {
+                    group.tiles.push(tile);
+                    group_of[tile] = groups.len();
+                    group.tiles.len() - 1
+                });
+                vars.push(var);
+                for &next in &numbers_at[tile] {
+                    if !number_done[next] {
+                        number_done[next] = true;
+                        queue.push(next);
+                    }
+                }
+            }
+            group.constraints.push(Constraint { vars, mines: *mines });
+        }
+        group.solve();
+        groups.push(group);
+    }
+
+    let mines = view.mines().saturating_sub(known_mines);
+    let unknown = hidden.iter().filter(|&&hidden| hidden).count();
+    let frontier: usize = groups.iter().map(|group| group.tiles.len()).sum();
+    let floating = unknown - frontier;
+
+    // weight[k] is how many ways the tiles away from the numbers can hold the other mines
+    // when the groups hold k, scaled so the largest weight is 1
+    let mut ln_factorials = vec![0.0; unknown.max(mines) + 1];
+    for n in 1..ln_factorials.len() {
+        ln_factorials[n] = ln_factorials[n - 1] + (n as f64).ln();
+    }
+    let ln_weights: Vec<f64> = (0..=mines)
+        .map(|k| {
+            if mines - k <= floating {
+                ln_choose(&ln_factorials, floating, mines - k)
+            } else {
+                f64::NEG_INFINITY
+            }
+        })
+        .collect();
+    let top = ln_weights.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let weights: Vec<f64> = ln_weights.iter().map(|&ln| (ln - top).exp()).collect();
+    let weight = |k: usize| weights.get(k).copied().unwrap_or(0.0);
+
+    let mut probabilities = vec![0.0; width * height];
+    let mut exact = groups.iter().all(|group| group.exact);
+    let everything = groups.iter().fold(vec![1.0], |acc, group| convolve(&acc, &group.layouts));
+
+    for (index, group) in groups.iter().enumerate() {
+        let others = groups
+            .iter()
+            .enumerate()
+            .filter(|&(other, _)| other != index)
+            .fold(vec![1.0], |acc, (_, other)| convolve(&acc, &other.layouts));
+        // rest[k] weighs every way the rest of the board can go when this group holds k
+        let rest: Vec<f64> = (0..group.layouts.len())
+            .map(|k| others.iter().enumerate().map(|(j, &ways)| ways * weight(k + j)).sum())
+            .collect();
+        let total: f64 = group.layouts.iter().zip(&rest).map(|(ways, rest)| ways * rest).sum();
+
+        for (var, &tile) in group.tiles.iter().enumerate() {
+            probabilities[tile] = if total > 0.0 {
+                group.mines_at[var].iter().zip(&rest).map(|(ways, rest)| ways * rest).sum::<f64>() / total
+            } else {
+                // the numbers contradict each other or the mine count
+                exact = false;
+                0.5
+            };
+        }
+    }
+
+    if floating > 0 {
+        let total: f64 = everything.iter().enumerate().map(|(k, &ways)| ways * weight(k)).sum();
+        let expected: f64 = everything
+            .iter()
+            .enumerate()
+            .filter(|&(k, _)| k <= mines)
+            .map(|(k, &ways)| ways * weight(k) * (mines - k) as f64)
+            .sum();
+        let chance = if total > 0.0 { expected / total / floating as f64 } else { 0.5 };
+        for tile in 0..width * height {
+            if hidden[tile] && group_of[tile] == usize::MAX {
+                probabilities[tile] = chance;
+            }
+        }
+    }
+
+    Analysis {
+        width,
+        probabilities,
+        hidden,
+        exact,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objs::{Board, GameResult};
+    use crate::save;
+
+    #[test]
+    fn finds_the_safe_tiles_and_the_mine() {
+        // the 1 in the middle only has the corner left for its mine
+        let board = save::board_from_str("minesweeper-save 1\nseed 0\nplacement pure-random\nscore 0\nboard 3 3\noo.\noo.\n..*\n").unwrap();
+        let analysis = analyze(board.view());
+        assert!(analysis.is_exact());
+        let mut safe = analysis.safe_tiles();
+        safe.sort();
+        assert_eq!(safe, vec![(0, 2), (1, 2), (2, 0), (2, 1)]);
+        assert_eq!(analysis.mines(), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn safe_tiles_and_mines_are_right_on_dealt_boards() {
+        for seed in 0..40 {
+            let mut board = Board::with_safe_start(9, 9, 10, seed % 2 == 0, seed, (4, 4)).unwrap();
+            board.reveal(4, 4);
+            while board.get_result() == GameResult::Continue {
+                let analysis = analyze(board.view());
+                if !analysis.is_exact() {
+                    break;
+                }
+                for (x, y) in analysis.mines() {
+                    assert!(board.get_tile(x, y).is_mine(), "seed {}: ({}, {}) isn't a mine", seed, x, y);
+                }
+                let safe = analysis.safe_tiles();
+                if safe.is_empty() {
+                    break;
+                }
+                for (x, y) in safe {
+                    assert!(!board.get_tile(x, y).is_mine(), "seed {}: ({}, {}) is a mine", seed, x, y);
+                    board.reveal(x, y);
+                }
+            }
+            assert_ne!(board.get_result(), GameResult::Lost, "seed {}", seed);
+        }
+    }
+}
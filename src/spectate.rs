@@ -0,0 +1,240 @@
+// live spectating. A game publishes every move over a local TCP socket as JSON lines and
+// spectators mirror it, read-only:
+//
+//   {"event": "start", "elapsed_ms": 0, "state": {"status": "continue", ..., "board": [...]}}
+//   {"event": "moves", "events": [{"event": "tile_revealed", "x": 3, "y": 4, "adjacent_mines": 1}],
+//    "elapsed_ms": 1840, "state": {...}}
+//   {"event": "moves", "events": [{"event": "flagged", "x": 5, "y": 4, "flagged": true}],
+//    "elapsed_ms": 2310, "state": {...}}
+//   {"event": "clock", "elapsed_ms": 4000, "state": {...}}
+//   {"event": "pause", "elapsed_ms": 4410, "state": {...}}
+//   {"event": "resume", "elapsed_ms": 4410, "state": {...}}
+//   {"event": "outcome", "elapsed_ms": 9120, "state": {...}}
+//
+// moves are the board's own events (events.rs), collected from the board the broadcaster
+// follows and sent together with the board they led to.
+//
+// `state` is the visible board from protocol.rs, so a spectator never learns more than the
+// player could see, and while the game is paused every tile goes out hidden. The time is the
+// game's own clock, so it stands still during a pause. Someone who joins halfway gets the
+// latest event straight away.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::BoardEvent;
+use crate::objs::{Board, GameResult};
+use crate::protocol::BoardState;
+use crate::view::Cell;
+
+pub const DEFAULT_SPECTATE_PORT: u16 = 7879;
+
+// how often the clock goes out while nobody is moving
+const CLOCK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum EventKind {
+    Start,
+    Moves { events: Vec<BoardEvent> },
+    Clock,
+    Pause,
+    Resume,
+    Outcome,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SpectatorEvent {
+    #[serde(flatten)]
+    pub kind: EventKind,
+    pub elapsed_ms: u64,
+    pub state: BoardState,
+}
+
+#[derive(Default)]
+struct Listeners {
+    senders: Vec<Sender<String>>,
+    // the latest event, for whoever connects next
+    last: Option<String>,
+}
+
+// the publishing side, owned by the game being watched
+pub struct Broadcaster {
+    listeners: Arc<Mutex<Listeners>>,
+    // events from the followed board that haven't gone out yet
+    moves: Arc<Mutex<Vec<BoardEvent>>>,
+    last_clock: Instant,
+}
+
+impl Broadcaster {
+    pub fn bind(address: &str) -> io::Result<Broadcaster> {
+        let listener = TcpListener::bind(address)?;
+        let listeners = Arc::new(Mutex::new(Listeners::default()));
+
+        let accepting = Arc::clone(&listeners);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (sender, receiver) = mpsc::channel::<String>();
+                let mut listeners = accepting.lock().unwrap();
+                if let Some(last) = &listeners.last {
+                    let _ = sender.send(last.clone());
+                }
+                listeners.senders.push(sender);
+                // a slow spectator only holds up its own thread, never the game
+                thread::spawn(move || write_lines(stream, receiver));
+            }
+        });
+
+        Ok(Broadcaster {
+            listeners,
+            moves: Arc::new(Mutex::new(Vec::new())),
+            last_clock: Instant::now(),
+        })
+    }
+
+    pub fn spectators(&self) -> usize {
+        self.listeners.lock().unwrap().senders.len()
+    }
+
+    /// Streams the moves made on this board from now on. A board that is swapped for
+    /// another has to be followed again.
+    pub fn follow(&mut self, board: &mut Board) {
+        // whatever is left belongs to the board that was followed before
+        self.moves.lock().unwrap().clear();
+        let moves = Arc::clone(&self.moves);
+        board.subscribe(move |event| moves.lock().unwrap().push(*event));
+    }
+
+    // moves go out ahead of anything else, so the stream stays in the order things happened
+    pub fn publish(&mut self, kind: EventKind, board: &Board) {
+        self.send_moves(board);
+        self.send(kind, board);
+    }
+
+    fn send_moves(&mut self, board: &Board) {
+        let events = std::mem::take(&mut *self.moves.lock().unwrap());
+        if !events.is_empty() {
+            self.send(EventKind::Moves { events }, board);
+        }
+    }
+
+    fn send(&mut self, kind: EventKind, board: &Board) {
+        self.last_clock = Instant::now();
+
+        let mut state = BoardState::new(board.view());
+        if board.is_paused() {
+            let hidden = Cell::Hidden.symbol().to_string().repeat(state.width as usize);
+            state.board.iter_mut().for_each(|row| *row = hidden.clone());
+        }
+        let event = SpectatorEvent {
+            kind,
+            elapsed_ms: board.get_elapsed_time().as_millis() as u64,
+            state,
+        };
+        let line = serde_json::to_string(&event).unwrap();
+        let mut listeners = self.listeners.lock().unwrap();
+        // spectators that hung up have dropped their receiver
+        listeners.senders.retain(|sender| sender.send(line.clone()).is_ok());
+        listeners.last = Some(line);
+    }
+
+    // call every frame, sends the moves made since the last frame and the clock once a
+    // second while the game is running
+    pub fn tick(&mut self, board: &Board) {
+        self.send_moves(board);
+        let running = board.has_started() && !board.is_paused() && board.get_result() == GameResult::Continue;
+        if running && self.last_clock.elapsed() >= CLOCK_INTERVAL {
+            self.send(EventKind::Clock, board);
+        }
+    }
+}
+
+fn write_lines(mut stream: TcpStream, receiver: Receiver<String>) {
+    for line in receiver {
+        if writeln!(stream, "{}", line).and_then(|_| stream.flush()).is_err() {
+            break;
+        }
+    }
+}
+
+// the watching side, shows events `delay` after they happened
+pub struct Spectator {
+    address: String,
+    delay: Duration,
+    receiver: Receiver<(Instant, SpectatorEvent)>,
+    pending: VecDeque<(Instant, SpectatorEvent)>,
+    current: Option<SpectatorEvent>,
+    disconnected: bool,
+}
+
+impl Spectator {
+    pub fn connect(address: &str, delay: Duration) -> io::Result<Spectator> {
+        let stream = TcpStream::connect(address)?;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+                // anything that doesn't parse is from a newer version, skip it
+                if let Ok(event) = serde_json::from_str::<SpectatorEvent>(&line) {
+                    if sender.send((Instant::now(), event)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Spectator {
+            address: address.to_string(),
+            delay,
+            receiver,
+            pending: VecDeque::new(),
+            current: None,
+            disconnected: false,
+        })
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    // the latest event that is old enough to show
+    pub fn poll(&mut self) -> Option<&SpectatorEvent> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => self.pending.push_back(event),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.disconnected = true;
+                    break;
+                }
+            }
+        }
+
+        while let Some((received, _)) = self.pending.front() {
+            if received.elapsed() < self.delay {
+                break;
+            }
+            self.current = self.pending.pop_front().map(|(_, event)| event);
+        }
+        self.current.as_ref()
+    }
+
+    // the game went away and everything it sent has been shown
+    pub fn is_finished(&self) -> bool {
+        self.disconnected && self.pending.is_empty()
+    }
+}
@@ -0,0 +1,114 @@
+// where a game is in its life. Frontends keep one of these instead of a flag per screen,
+// and only move it along the transitions below
+
+use serde::{Deserialize, Serialize};
+
+use crate::objs::{Board, GameResult};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameState {
+    // picking a game, or on one of the screens off the menu
+    #[default]
+    Menu,
+    // a board is up but nothing has been revealed on it yet
+    Ready,
+    Playing,
+    Paused,
+    Won,
+    Lost,
+    // watching a game instead of playing one
+    Replaying,
+}
+
+impl GameState {
+    /// The state a board is in on its own, e.g. after loading it from a save.
+    pub fn of_board(board: &Board) -> GameState {
+        match board.get_result() {
+            GameResult::Won => GameState::Won,
+            GameResult::Lost => GameState::Lost,
+            GameResult::Continue if board.has_started() => GameState::Playing,
+            GameResult::Continue => GameState::Ready,
+        }
+    }
+
+    /// Whether the game can go straight from this state to `next`.
+    pub fn can_move_to(self, next: GameState) -> bool {
+        use GameState::*;
+        match (self, next) {
+            // going back to the menu, starting a new board or watching one works from anywhere
+            (_, Menu) | (_, Ready) | (_, Replaying) => true,
+            (Ready, Playing) | (Paused, Playing) => true,
+            (Playing, Paused) => true,
+            // the very first reveal can already end the game
+            (Ready, Won | Lost) | (Playing, Won | Lost) => true,
+            _ => false,
+        }
+    }
+
+    /// Moves to `next` if that's a legal transition, otherwise stays put.
+    /// Returns whether the state changed.
+    pub fn move_to(&mut self, next: GameState) -> bool {
+        if *self == next || !self.can_move_to(next) {
+            return false;
+        }
+        *self = next;
+        true
+    }
+
+    /// Moves along with what a move did to the board: the first one starts the
+    /// game and a won or lost board ends it.
+    pub fn apply(&mut self, result: GameResult) -> bool {
+        match result {
+            GameResult::Continue => self.move_to(GameState::Playing),
+            GameResult::Won => self.move_to(GameState::Won),
+            GameResult::Lost => self.move_to(GameState::Lost),
+        }
+    }
+
+    /// Whether a board is on screen, finished or not.
+    pub fn in_game(self) -> bool {
+        !matches!(self, GameState::Menu | GameState::Replaying)
+    }
+
+    pub fn is_over(self) -> bool {
+        matches!(self, GameState::Won | GameState::Lost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GameState::*;
+
+    #[test]
+    fn games_move_through_their_states() {
+        assert!(Ready.can_move_to(Playing));
+        assert!(Playing.can_move_to(Paused));
+        assert!(Paused.can_move_to(Playing));
+        assert!(Ready.can_move_to(Lost));
+        assert!(Playing.can_move_to(Won));
+    }
+
+    #[test]
+    fn finished_and_paused_games_stay_put() {
+        assert!(!Won.can_move_to(Playing));
+        assert!(!Lost.can_move_to(Paused));
+        assert!(!Paused.can_move_to(Won));
+        assert!(!Menu.can_move_to(Playing));
+        assert!(!Ready.can_move_to(Paused));
+
+        let mut state = Lost;
+        assert!(!state.move_to(Playing));
+        assert_eq!(state, Lost);
+    }
+
+    #[test]
+    fn anything_can_go_back_to_the_menu_or_a_new_board() {
+        for state in [Menu, Ready, Playing, Paused, Won, Lost, Replaying] {
+            assert!(state.can_move_to(Menu));
+            assert!(state.can_move_to(Ready));
+            assert!(state.can_move_to(Replaying));
+        }
+    }
+}
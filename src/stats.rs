@@ -0,0 +1,202 @@
+// statistics kept across sessions, one JSON line per finished game
+
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::objs::{Board, GameResult};
+use crate::storage;
+
+pub const STATS_FILE: &str = "minesweeper-stats.jsonl";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    Easy,
+    Medium,
+    Hard,
+    Custom,
+    Daily,
+    #[serde(rename = "time_attack")]
+    TimeAttack,
+    // any size played with more than one life
+    Lives,
+}
+
+impl GameMode {
+    pub fn from_difficulty(difficulty: Difficulty) -> GameMode {
+        match difficulty {
+            Difficulty::Easy => GameMode::Easy,
+            Difficulty::Medium => GameMode::Medium,
+            Difficulty::Hard => GameMode::Hard,
+        }
+    }
+
+    // presets are recognised by their size, anything else is custom
+    pub fn of_board(board: &Board) -> GameMode {
+        if board.has_lives() {
+            return GameMode::Lives;
+        }
+        match Difficulty::matching(board.get_width() as usize, board.get_height() as usize, board.get_mines() as usize) {
+            Some(difficulty) => GameMode::from_difficulty(difficulty),
+            None => GameMode::Custom,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Won,
+    Lost,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub mode: GameMode,
+    pub width: u8,
+    pub height: u8,
+    pub mines: u8,
+    pub outcome: Outcome,
+    pub time_ms: u64,
+    pub bv3: u32,
+    pub clicks: u32,
+    // older records were all played with a single life
+    #[serde(default)]
+    pub lives_used: u8,
+    pub seed: u64,
+    pub pure_random: bool,
+    // unix seconds
+    pub finished_at: u64,
+}
+
+impl GameRecord {
+    // None while the game is still going
+    pub fn from_board(board: &Board) -> Option<GameRecord> {
+        let outcome = match board.get_result() {
+            GameResult::Won => Outcome::Won,
+            GameResult::Lost => Outcome::Lost,
+            GameResult::Continue => return None,
+        };
+        Some(GameRecord {
+            mode: GameMode::of_board(board),
+            width: board.get_width(),
+            height: board.get_height(),
+            mines: board.get_mines(),
+            outcome,
+            time_ms: board.get_elapsed_time().as_millis() as u64,
+            bv3: board.get_3bv(),
+            clicks: board.get_clicks(),
+            lives_used: board.get_lives_used(),
+            seed: board.get_seed(),
+            pure_random: board.is_pure_random(),
+            finished_at: storage::now_timestamp(),
+        })
+    }
+
+    pub fn time(&self) -> Duration {
+        Duration::from_millis(self.time_ms)
+    }
+
+    // 3BV per second, the usual measure of how efficiently a board was played
+    pub fn bv3_per_second(&self) -> f64 {
+        if self.time_ms == 0 {
+            return 0.0;
+        }
+        self.bv3 as f64 / (self.time_ms as f64 / 1000.0)
+    }
+}
+
+pub fn stats_path() -> PathBuf {
+    storage::data_path(STATS_FILE)
+}
+
+pub fn append_record(path: &Path, record: &GameRecord) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(record)?)
+}
+
+// records in the order they were played, lines that don't parse are skipped
+pub fn load_records(path: &Path) -> io::Result<Vec<GameRecord>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(record) = serde_json::from_str(&line?) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+// records a finished game in the default stats file
+pub fn record_game(board: &Board) -> io::Result<()> {
+    record_game_as(board, GameMode::of_board(board))
+}
+
+// for games that aren't told apart by their size, like the daily challenge
+pub fn record_game_as(board: &Board, mode: GameMode) -> io::Result<()> {
+    // hand made and loaded boards could be set up to give any time at all
+    if !board.is_ranked() {
+        return Ok(());
+    }
+    match GameRecord::from_board(board) {
+        Some(record) => append_record(&stats_path(), &GameRecord { mode, ..record }),
+        None => Ok(()),
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub games: u32,
+    pub wins: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub best_time: Option<Duration>,
+    pub average_time: Option<Duration>,
+}
+
+impl Summary {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+}
+
+// streaks count wins in a row, times only count won games
+pub fn summarize(records: &[GameRecord], mode: GameMode) -> Summary {
+    let mut summary = Summary::default();
+    let mut total_time = Duration::ZERO;
+
+    for record in records.iter().filter(|record| record.mode == mode) {
+        summary.games += 1;
+        match record.outcome {
+            Outcome::Won => {
+                summary.wins += 1;
+                summary.current_streak += 1;
+                summary.best_streak = summary.best_streak.max(summary.current_streak);
+                total_time += record.time();
+                summary.best_time = Some(match summary.best_time {
+                    Some(best) => best.min(record.time()),
+                    None => record.time(),
+                });
+            }
+            Outcome::Lost => summary.current_streak = 0,
+        }
+    }
+
+    if summary.wins > 0 {
+        summary.average_time = Some(total_time / summary.wins);
+    }
+    summary
+}
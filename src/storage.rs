@@ -0,0 +1,61 @@
+// where local data files (statistics, high scores, ...) are kept
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// set this to keep the data files somewhere other than the working directory
+pub const DATA_DIR_VAR: &str = "MINESWEEPER_DATA_DIR";
+
+pub fn data_path(file_name: &str) -> PathBuf {
+    match std::env::var_os(DATA_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir).join(file_name),
+        None => PathBuf::from(file_name),
+    }
+}
+
+// seconds since the unix epoch
+pub fn now_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+// reads a JSON data file, one that hasn't been written yet reads as the default
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(json) => Ok(serde_json::from_str(&json)?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err),
+    }
+}
+
+// writes a JSON data file, pretty printed so it can be read and edited by hand
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn json_files_read_back_what_was_written() {
+        let path = std::env::temp_dir().join(format!("minesweeper-storage-test-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let missing: BTreeMap<String, u32> = load_json(&path).unwrap();
+        assert!(missing.is_empty());
+
+        let value = BTreeMap::from([("boards".to_string(), 3), ("score".to_string(), 120)]);
+        save_json(&path, &value).unwrap();
+        assert_eq!(load_json::<BTreeMap<String, u32>>(&path).unwrap(), value);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load_json::<BTreeMap<String, u32>>(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
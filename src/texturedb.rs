@@ -1,7 +1,5 @@
-use egui::{Image, TextBuffer, TextureHandle, Ui};
-
 pub struct TextureDatabase {
-    texture: Option<egui::TextureHandle>,
+    asset_dir: std::path::PathBuf,
     base: Option<egui::TextureHandle>,
     flag: Option<egui::TextureHandle>,
     mine: Option<egui::TextureHandle>,
@@ -32,6 +30,24 @@ pub struct ImageDatabase {
 }
 
 impl TextureDatabase {
+    pub fn new(asset_dir: &std::path::Path) -> Self {
+        Self {
+            asset_dir: asset_dir.to_path_buf(),
+            base: None,
+            flag: None,
+            mine: None,
+            zero: None,
+            one: None,
+            two: None,
+            three: None,
+            four: None,
+            five: None,
+            six: None,
+            seven: None,
+            eight: None,
+        }
+    }
+
     pub fn update_all(&mut self, ui: &mut egui::Ui) {
         self.update_with_ui(ui, "base");
         self.update_with_ui(ui, "flag");
@@ -47,7 +63,7 @@ impl TextureDatabase {
         self.update_with_ui(ui, "eight");
     }
     pub(crate) fn update_with_ui(&mut self, ui: &mut egui::Ui, image: &str) -> &egui::TextureHandle {
-        let image_db = ImageDatabase::default();
+        let image_db = ImageDatabase::load(&self.asset_dir);
         let texture = match image {
             "base" => {
                 let image_file = image_db.base;
@@ -63,7 +79,7 @@ impl TextureDatabase {
                 let image_file = image_db.flag;
                 self.flag.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -73,7 +89,7 @@ impl TextureDatabase {
                 let image_file = image_db.mine;
                 self.mine.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -83,7 +99,7 @@ impl TextureDatabase {
                 let image_file = image_db.zero;
                 self.zero.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -93,7 +109,7 @@ impl TextureDatabase {
                 let image_file = image_db.one;
                 self.one.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -103,7 +119,7 @@ impl TextureDatabase {
                 let image_file = image_db.two;
                 self.two.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -113,7 +129,7 @@ impl TextureDatabase {
                 let image_file = image_db.three;
                 self.three.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -123,7 +139,7 @@ impl TextureDatabase {
                 let image_file = image_db.four;
                 self.four.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -133,7 +149,7 @@ impl TextureDatabase {
                 let image_file = image_db.five;
                 self.five.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -143,7 +159,7 @@ impl TextureDatabase {
                 let image_file = image_db.six;
                 self.six.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -153,7 +169,7 @@ impl TextureDatabase {
                 let image_file = image_db.seven;
                 self.seven.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -163,7 +179,7 @@ impl TextureDatabase {
                 let image_file = image_db.eight;
                 self.eight.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -173,7 +189,7 @@ impl TextureDatabase {
                 let image_file = image_db.base;
                 self.base.get_or_insert_with(|| {
                     ui.ctx().load_texture(
-                        image.as_str(),
+                        image,
                         image_file,
                         egui::TextureFilter::Linear
                     )
@@ -202,26 +218,6 @@ impl TextureDatabase {
     }
 }
 
-impl Default for TextureDatabase {
-    fn default() -> Self {
-        Self {
-            texture: None,
-            base: None,
-            flag: None,
-            mine: None,
-            zero: None,
-            one: None,
-            two: None,
-            three: None,
-            four: None,
-            five: None,
-            six: None,
-            seven: None,
-            eight: None
-        }
-    }
-}
-
 impl ImageDatabase {
     pub fn load_image_from_path(path: &std::path::Path) -> Result<egui::ColorImage, image::ImageError> {
         let image = image::io::Reader::open(path)?.decode()?;
@@ -235,21 +231,20 @@ impl ImageDatabase {
     }
 }
 
-impl Default for ImageDatabase {
-
-    fn default() -> Self {
-        let base = ImageDatabase::load_image_from_path(std::path::Path::new("assets/base.png")).unwrap();
-        let flag = ImageDatabase::load_image_from_path(std::path::Path::new("assets/f.png")).unwrap();
-        let mine = ImageDatabase::load_image_from_path(std::path::Path::new("assets/b.png")).unwrap();
-        let zero = ImageDatabase::load_image_from_path(std::path::Path::new("assets/0.png")).unwrap();
-        let one = ImageDatabase::load_image_from_path(std::path::Path::new("assets/1.png")).unwrap();
-        let two = ImageDatabase::load_image_from_path(std::path::Path::new("assets/2.png")).unwrap();
-        let three = ImageDatabase::load_image_from_path(std::path::Path::new("assets/3.png")).unwrap();
-        let four = ImageDatabase::load_image_from_path(std::path::Path::new("assets/4.png")).unwrap();
-        let five = ImageDatabase::load_image_from_path(std::path::Path::new("assets/5.png")).unwrap();
-        let six = ImageDatabase::load_image_from_path(std::path::Path::new("assets/6.png")).unwrap();
-        let seven = ImageDatabase::load_image_from_path(std::path::Path::new("assets/7.png")).unwrap();
-        let eight = ImageDatabase::load_image_from_path(std::path::Path::new("assets/8.png")).unwrap();
+impl ImageDatabase {
+    pub fn load(asset_dir: &std::path::Path) -> Self {
+        let base = ImageDatabase::load_image_from_dir(asset_dir, "base.png");
+        let flag = ImageDatabase::load_image_from_dir(asset_dir, "f.png");
+        let mine = ImageDatabase::load_image_from_dir(asset_dir, "b.png");
+        let zero = ImageDatabase::load_image_from_dir(asset_dir, "0.png");
+        let one = ImageDatabase::load_image_from_dir(asset_dir, "1.png");
+        let two = ImageDatabase::load_image_from_dir(asset_dir, "2.png");
+        let three = ImageDatabase::load_image_from_dir(asset_dir, "3.png");
+        let four = ImageDatabase::load_image_from_dir(asset_dir, "4.png");
+        let five = ImageDatabase::load_image_from_dir(asset_dir, "5.png");
+        let six = ImageDatabase::load_image_from_dir(asset_dir, "6.png");
+        let seven = ImageDatabase::load_image_from_dir(asset_dir, "7.png");
+        let eight = ImageDatabase::load_image_from_dir(asset_dir, "8.png");
         Self {
             base,
             flag,
@@ -265,4 +260,24 @@ impl Default for ImageDatabase {
             eight,
         }
     }
-}
\ No newline at end of file
+
+    fn load_image_from_dir(asset_dir: &std::path::Path, file_name: &str) -> egui::ColorImage {
+        let path = asset_dir.join(file_name);
+        match ImageDatabase::load_image_from_path(&path) {
+            Ok(image) => image,
+            Err(err) => panic!("could not load {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl Default for TextureDatabase {
+    fn default() -> Self {
+        Self::new(std::path::Path::new("assets"))
+    }
+}
+
+impl Default for ImageDatabase {
+    fn default() -> Self {
+        Self::load(std::path::Path::new("assets"))
+    }
+}
@@ -0,0 +1,127 @@
+// time attack, the board has to be cleared before the countdown runs out.
+// The bonus variant gives time back for every opening cleared and every mine flagged correctly
+
+use std::time::Duration;
+
+use crate::objs::{Board, GameResult};
+
+pub const DEFAULT_LIMIT: Duration = Duration::from_secs(60);
+pub const OPENING_BONUS: Duration = Duration::from_secs(5);
+pub const FLAG_BONUS: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeAttack {
+    pub limit: Duration,
+    pub opening_bonus: Duration,
+    pub flag_bonus: Duration,
+}
+
+impl TimeAttack {
+    pub fn new(limit: Duration) -> TimeAttack {
+        TimeAttack {
+            limit,
+            opening_bonus: Duration::ZERO,
+            flag_bonus: Duration::ZERO,
+        }
+    }
+
+    pub fn with_bonus(limit: Duration) -> TimeAttack {
+        TimeAttack {
+            limit,
+            opening_bonus: OPENING_BONUS,
+            flag_bonus: FLAG_BONUS,
+        }
+    }
+
+    pub fn has_bonus(&self) -> bool {
+        !self.opening_bonus.is_zero() || !self.flag_bonus.is_zero()
+    }
+
+    /// Time earned on the board so far.
+    pub fn bonus(&self, board: &Board) -> Duration {
+        if !self.has_bonus() {
+            return Duration::ZERO;
+        }
+        self.opening_bonus * openings_cleared(board) + self.flag_bonus * confirmed_flags(board)
+    }
+
+    pub fn remaining(&self, board: &Board) -> Duration {
+        (self.limit + self.bonus(board)).saturating_sub(board.get_elapsed_time())
+    }
+
+    /// Ends the game as lost once the time has run out, call it every frame.
+    pub fn check(&self, board: &mut Board) -> GameResult {
+        if board.get_result() == GameResult::Continue && self.remaining(board).is_zero() {
+            return board.lose();
+        }
+        board.get_result()
+    }
+}
+
+// openings with anything revealed in them, revealing one tile of an opening opens all of it
+fn openings_cleared(board: &Board) -> u32 {
+    let width = board.get_width() as usize;
+    let height = board.get_height() as usize;
+    let mut reached = vec![false; width * height];
+    let mut cleared = 0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let tile = board.get_tile(x, y);
+            if tile.is_mine || tile.adjacent_mines != 0 || reached[y * width + x] {
+                continue;
+            }
+            if tile.is_revealed {
+                cleared += 1;
+            }
+
+            reached[y * width + x] = true;
+            let mut pending = vec![(x, y)];
+            while let Some((x, y)) = pending.pop() {
+                for (x, y) in board.neighbours(x, y) {
+                    let tile = board.get_tile(x, y);
+                    if tile.adjacent_mines == 0 && !tile.is_mine && !reached[y * width + x] {
+                        reached[y * width + x] = true;
+                        pending.push((x, y));
+                    }
+                }
+            }
+        }
+    }
+    cleared
+}
+
+// flagged mines with every neighbour revealed. Flags only count once the area around them is
+// settled, and a neighbouring flag is only the player's guess. Counting those would let the
+// clock tell whether a guess was a mine
+fn confirmed_flags(board: &Board) -> u32 {
+    let mut confirmed = 0;
+    for y in 0..board.get_height() as usize {
+        for x in 0..board.get_width() as usize {
+            let tile = board.get_tile(x, y);
+            if !tile.is_mine || !tile.is_flagged {
+                continue;
+            }
+            // mines that went off are revealed too
+            let solved = board.neighbours(x, y).into_iter().all(|(x, y)| board.get_tile(x, y).is_revealed);
+            if solved {
+                confirmed += 1;
+            }
+        }
+    }
+    confirmed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save::board_from_str;
+
+    #[test]
+    fn flags_next_to_other_flags_are_not_confirmed() {
+        let board = board_from_str("minesweeper-save 1\nseed 1\nplacement pure-random\nscore 0\nboard 5 3\nFFoo.\nooooo\nooooF\n").unwrap();
+        assert_eq!(confirmed_flags(&board), 1);
+        let flags_only = TimeAttack { opening_bonus: Duration::ZERO, ..TimeAttack::with_bonus(DEFAULT_LIMIT) };
+        assert_eq!(flags_only.bonus(&board), FLAG_BONUS);
+    }
+}
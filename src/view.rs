@@ -0,0 +1,122 @@
+// what a player is allowed to see of a board. Frontends, bots and solvers read the board
+// through this so none of them can peek at mines that haven't been found yet.
+
+use crate::objs::{Board, GameResult, Tile};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Hidden,
+    Flagged,
+    // a revealed safe tile and how many mines are around it
+    Number(u8),
+    // a revealed mine, after a loss or when claimed in Minesweeper Flags
+    Mine,
+}
+
+impl Cell {
+    pub(crate) fn of(tile: &Tile) -> Cell {
+        if tile.is_revealed() {
+            if tile.is_mine() {
+                Cell::Mine
+            } else {
+                Cell::Number(tile.get_adjacent_mines())
+            }
+        } else if tile.is_flagged() {
+            Cell::Flagged
+        } else {
+            Cell::Hidden
+        }
+    }
+
+    // how the cell is written in the protocols, see protocol.rs
+    pub fn symbol(&self) -> char {
+        match self {
+            Cell::Hidden => '#',
+            Cell::Flagged => 'F',
+            Cell::Number(adjacent_mines) => (b'0' + adjacent_mines) as char,
+            Cell::Mine => '*',
+        }
+    }
+
+    pub fn from_symbol(symbol: char) -> Option<Cell> {
+        match symbol {
+            '#' => Some(Cell::Hidden),
+            'F' => Some(Cell::Flagged),
+            '*' => Some(Cell::Mine),
+            '0'..='8' => Some(Cell::Number(symbol as u8 - b'0')),
+            _ => None,
+        }
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        matches!(self, Cell::Number(_) | Cell::Mine)
+    }
+}
+
+/// A read-only view of a board that only shows what the player can see.
+#[derive(Clone, Copy)]
+pub struct PlayerView<'a> {
+    board: &'a Board,
+}
+
+impl<'a> PlayerView<'a> {
+    pub(crate) fn new(board: &'a Board) -> PlayerView<'a> {
+        PlayerView { board }
+    }
+
+    pub fn width(&self) -> usize {
+        self.board.get_width() as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.board.get_height() as usize
+    }
+
+    // panics when (x, y) is off the board, check it against width() and height() first
+    pub fn cell(&self, x: usize, y: usize) -> Cell {
+        Cell::of(self.board.get_tile(x, y))
+    }
+
+    pub fn result(&self) -> GameResult {
+        self.board.get_result()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.board.get_result() != GameResult::Continue
+    }
+
+    pub fn mines(&self) -> usize {
+        self.board.get_mines() as usize
+    }
+
+    pub fn flags(&self) -> usize {
+        self.board.get_flags() as usize
+    }
+
+    // safe tiles revealed, plus the bonus for flagged mines once a game is lost
+    pub fn score(&self) -> u32 {
+        self.board.get_score()
+    }
+
+    // mines hit while playing with lives
+    pub fn lives_used(&self) -> usize {
+        self.board.get_lives_used() as usize
+    }
+
+    // mines minus flags and mines that went off, negative when there are more flags than mines
+    pub fn remaining_mines(&self) -> i32 {
+        self.mines() as i32 - self.flags() as i32 - self.lives_used() as i32
+    }
+
+    // where the mines are, only once the game is over. Panics like cell() off the board
+    pub fn is_mine(&self, x: usize, y: usize) -> Option<bool> {
+        if !self.is_over() {
+            return None;
+        }
+        Some(self.board.get_tile(x, y).is_mine())
+    }
+
+    pub fn neighbours(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        self.board.neighbours(x, y)
+    }
+}